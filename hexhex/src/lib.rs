@@ -5,10 +5,24 @@
 Features:
 
 - Display bytes as hex with no (heap) allocations
+- Display bytes as 16, 32, or 64-bit words in either byte order, like `xxd -e`
 - Convert bytes to hex `String`
 - Convert hex `&str` or `&[u8]` to a new byte vector
 - Convert hex `&str` or `&[u8]` to bytes in a preallocated buffer
+- Convert hex to 16, 32, or 64-bit words, big or little endian
+- Decode lazily through a standard `Iterator`
+- Iterate, decode and display nibbles (half-bytes), including odd numbers of hex digits
+- Format and parse hexadecimal floating-point numbers like C's `%a`
+- Append decoded bytes to an existing `Vec`, `BytesMut`, `heapless::Vec`, or `ArrayVec`
+- Decode in place, within the buffer holding the input
+- Constant-time encoding and decoding for secret material, and optional `zeroize` support
+- Decode errors with line, column, and source snippet (and optionally `miette` diagnostics)
+- Convert `xxd` and `hexdump -C` output back to bytes
+- Read and write Intel HEX and Motorola S-record files
+- Read and write Verilog `$readmemh` memory files
 - Macro for all your compile-time hex to bytes conversion needs
+- Include hex text files at compile time
+- `const fn` decoding into arrays and encoding from arrays, also without the macro
 - `#![no_std]` support for a subset of the above
 - No runtime panics (except for internal bugs)
 
@@ -28,6 +42,14 @@ let bytes = [0xc0, 0xff, 0xee];
 println!("{}", Hex::new(&bytes).with_prefix(true).with_case(Case::Upper)); // no allocations, prints "0xC0FFEE"
 ```
 
+To inspect memory like `xxd -e -g4` or `od -x` does, [`HexWords`] groups the bytes into words:
+
+```
+use hexhex::{hex_words, Endian};
+let bytes = [0x78, 0x56, 0x34, 0x12, 0xef, 0xbe, 0xad, 0xde];
+println!("{}", hex_words(&bytes).with_endian(Endian::Little)); // prints "12345678 deadbeef"
+```
+
 ## Encode to String
 
 `Hex` implements the [`core::fmt::Display`] trait, so conversion to string is as easy as:
@@ -46,6 +68,28 @@ use hexhex::Hex;
 assert_eq!(Hex::new(b"").with_prefix(true).to_string(), "0x");
 ```
 
+## Encode at compile time
+
+[`encode_array`] is a `const fn`, so the hex form of constants can be embedded in `static` strings
+without formatting at runtime:
+
+```
+use hexhex::{encode_array, encoded_str, Case};
+const KEY_ID: [u8; 3] = [0xc0, 0xff, 0xee];
+static KEY_ID_HEX: [u8; 6] = encode_array(&KEY_ID, Case::Lower);
+static KEY_ID_STR: &str = encoded_str(&KEY_ID_HEX);
+assert_eq!(KEY_ID_STR, "c0ffee");
+```
+
+At runtime, [`encode_in_place`] turns bytes into hex within a single buffer, also without formatting:
+
+```
+use hexhex::{encode_in_place, Case};
+let mut serial = [0u8; 8];
+serial[..4].copy_from_slice(&[0x00, 0x12, 0xab, 0xcd]);
+assert_eq!(encode_in_place(&mut serial, Case::Upper), "0012ABCD");
+```
+
 ## Decoding (no allocations)
 
 ```
@@ -57,8 +101,68 @@ assert_eq!(buf, [0xc0, 0xff, 0xee]);
 ```
 
 There are some other variants, check the list of functions to see them all.
+The `with_options` variants take [`DecodeOptions`] to control which prefixes, letter cases, and separators are accepted:
+
+```
+use hexhex::{decode_to_buf_exact_with_options, CasePolicy, DecodeOptions, PrefixPolicy};
+let options = DecodeOptions::new()
+    .with_prefix(PrefixPolicy::Forbidden)
+    .with_case(CasePolicy::Lower)
+    .with_separators(":");
+let mut buf = [0u8; 3];
+assert!(decode_to_buf_exact_with_options("c0:ff:ee", &mut buf, options).is_ok());
+assert_eq!(buf, [0xc0, 0xff, 0xee]);
+assert!(decode_to_buf_exact_with_options("C0:FF:EE", &mut buf, options).is_err());
+```
+
 The `ascii` variants take byte strings (`&[u8]`) which need not contain ASCII or UTF-8 (however, only valid ASCII can be valid hex strings).
 
+Errors tell what went wrong, and where:
+
+```
+use hexhex::{decode_to_buf, FromHexErrorKind};
+let mut buf = [0u8; 2];
+let err = decode_to_buf("c0ffee", &mut buf).unwrap_err();
+assert_eq!(err.kind(), FromHexErrorKind::OutputTooSmall { required: 3 });
+assert_eq!(err.position(), 4);
+```
+
+To check input without decoding it, use [`validate`], which also tells how many bytes the input decodes to.
+To find all errors at once instead of just the first, use [`validate_all`]:
+
+```
+use hexhex::{validate_all, FromHexErrorKind};
+let kinds: Vec<_> = validate_all("c0fge!").map(|e| e.kind()).collect();
+assert_eq!(
+    kinds,
+    [FromHexErrorKind::UnexpectedCharacter('g'), FromHexErrorKind::UnexpectedCharacter('!')]
+);
+```
+
+The position is in bytes. Given the input, they can also tell the line and column, or show where exactly the input went wrong:
+
+```
+use hexhex::{decode_to_buf_with_options, DecodeOptions};
+let input = "c0ffee\nc0fxee";
+let mut buf = [0u8; 6];
+let options = DecodeOptions::new().with_separators("\n");
+let err = decode_to_buf_with_options(input, &mut buf, options).unwrap_err();
+assert_eq!(err.line_column(input), (2, 4));
+println!("{}", err.snippet(input));
+// error converting from hex: unexpected character x at line 2, column 4
+//   |
+// 2 | c0fxee
+//   |    ^
+```
+
+Bytes can also be decoded lazily, as an ordinary [`Iterator`] with an exact length:
+
+```
+use hexhex::decode_iter;
+let checksum = decode_iter("c0ffee").try_fold(0u8, |sum, byte| byte.map(|b| sum.wrapping_add(b)));
+assert_eq!(checksum.unwrap(), 0xad);
+```
+
 ## Decoding (std)
 
 ```
@@ -77,6 +181,147 @@ assert_eq!(&decode_ascii(b"c0ffee").unwrap(), &[0xc0, 0xff, 0xee]);
 }
 ```
 
+## Decoding into existing buffers
+
+[`decode_into`] appends to anything implementing [`DecodeSink`], without zero-filling it first.
+The input is validated before anything is appended:
+
+```
+#[cfg(feature = "std")]
+{
+use hexhex::decode_into;
+let mut bytes = b"id:".to_vec();
+decode_into("c0ffee", &mut bytes).unwrap();
+assert_eq!(bytes, b"id:\xc0\xff\xee");
+}
+```
+
+With the `bytes`, `heapless`, or `arrayvec` features, the same works for `BytesMut` (and any `BufMut` via `BufMutSink`),
+`heapless::Vec`, and `ArrayVec`. If the bytes do not fit, the error kind is [`FromHexErrorKind::OutputTooSmall`].
+
+[`decode_in_place`] decodes into the front of the buffer holding the input, so no second buffer is needed:
+
+```
+use hexhex::decode_in_place;
+let mut buf = *b"0xc0ffee";
+assert_eq!(decode_in_place(&mut buf).unwrap(), [0xc0, 0xff, 0xee]);
+```
+
+## Words
+
+[`decode_words`] decodes into 16, 32, 64-bit (or 8 and 128-bit) words of either byte order,
+and checks that the input consists of whole words:
+
+```
+use hexhex::{decode_words, Endian, FromHexErrorKind};
+let mut registers = [0u32; 4];
+assert_eq!(decode_words("0x0000ff0012345678", Endian::Big, &mut registers).unwrap(), 2);
+assert_eq!(registers[..2], [0xff00, 0x12345678]);
+let err = decode_words("0000ff001234", Endian::Big, &mut registers).unwrap_err();
+assert_eq!((err.position(), err.kind()), (8, FromHexErrorKind::IncompleteWord));
+```
+
+With `std`, `decode_words_vec` returns a new `Vec` instead.
+
+## Nibbles
+
+[`Nibbles`] views bytes as a sequence of half-bytes, high or low nibble first, and may have an odd length.
+[`decode_nibbles`] decodes any number of hex digits into one, without an odd length error:
+
+```
+use hexhex::decode_nibbles;
+let mut buf = [0u8; 2];
+let nibbles = decode_nibbles("abc", &mut buf).unwrap();
+assert_eq!(nibbles.len(), 3);
+assert_eq!(nibbles[1], 0xb);
+assert_eq!(nibbles.with_prefix(true).to_string(), "0xabc");
+```
+
+## Hex floats
+
+The [`float`] module formats `f32` and `f64` like C's `%a`, and parses them back exactly:
+
+```
+use hexhex::float::{hex_float, parse_f32};
+assert_eq!(hex_float(0.1f32).to_string(), "0x1.99999ap-4");
+assert_eq!(parse_f32("0x1.99999ap-4").unwrap(), 0.1);
+```
+
+## Secrets
+
+The [`ct`] module encodes and decodes without branches or table lookups that depend on the data,
+for keys and other secrets. Errors are reported only as a single validity flag:
+
+```
+use hexhex::ct;
+let mut key = [0u8; 4];
+ct::decode("c0ffee00", &mut key).unwrap();
+assert_eq!(key, [0xc0, 0xff, 0xee, 0x00]);
+assert!(ct::decode("c0ffee0g", &mut key).is_err());
+```
+
+With the `zeroize` feature, `decode_zeroizing` and its array and in-place variants wipe the decoded bytes when they are dropped,
+and on error. `ZeroizingInput` wipes the input of the streaming iterators as it is read.
+Formatting with [`Hex`] or [`HexWords`] cannot guarantee that no copies of the digits are left behind,
+so encode secrets with [`ct::encode`] or [`encode_in_place`] into a `zeroize::Zeroizing` buffer instead.
+
+## Hex dumps
+
+The output of `xxd` and `hexdump -C` can be turned back into bytes, like `xxd -r` does:
+
+```
+#[cfg(feature = "std")]
+{
+use hexhex::decode_dump;
+let dump = "00000000: 4865 6c6c 6f2c 2077 6f72 6c64 210a       Hello, world!.";
+assert_eq!(decode_dump(dump).unwrap(), b"Hello, world!\n");
+}
+```
+
+[`DumpDecodeIterator`] does the same without allocating.
+
+## Intel HEX
+
+The [`ihex`] module reads and writes Intel HEX files:
+
+```
+#[cfg(feature = "std")]
+{
+use hexhex::ihex;
+let file = ":0400000001020304F2\n:00000001FF\n";
+let ihex = ihex::read(file).unwrap();
+assert_eq!(ihex.image.get(0), Some(0x01));
+assert_eq!(ihex.to_string(), file);
+}
+```
+
+## Motorola S-records
+
+The [`srec`] module reads and writes S19, S28, and S37 files:
+
+```
+#[cfg(feature = "std")]
+{
+use hexhex::srec;
+let srec = srec::read("S107000001020304EE\nS9030000FC\n").unwrap();
+assert_eq!(srec.image.get(3), Some(0x04));
+}
+```
+
+## Verilog memory files
+
+The [`readmemh`] module reads and writes `$readmemh` files, as word arrays or byte images:
+
+```
+#[cfg(feature = "std")]
+{
+use hexhex::readmemh::{self, ReadOptions};
+let options = ReadOptions { word_width: 32, ..Default::default() };
+let words = readmemh::read_words("@2 deadbeef // comment", options).unwrap();
+assert_eq!(words, [0, 0, 0xdeadbeef]);
+}
+```
+
 ## Macro
 
 ```
@@ -92,6 +337,33 @@ use hexhex::hex_literal;
 let bytes = hex_literal!("c0f"); // odd number of hex digits is invalid
 ```
 
+Inputs with an odd number of hex digits can be padded with a leading zero instead:
+
+```
+use hexhex::hex_literal;
+let bytes: &[u8; 2] = hex_literal!("0xfff", pad_left);
+assert_eq!(bytes, &[0x0f, 0xff]);
+```
+
+Long inputs can be split into several literals, with whitespace between bytes and `//` comments:
+
+```
+use hexhex::hex_literal;
+let key: &[u8; 16] = hex_literal!(
+    "00112233 44556677"
+    "8899aabb ccddeeff" // second half
+);
+assert_eq!(key[8], 0x88);
+```
+
+[`include_hex!`] does the same for a file of hex text, like `include_bytes!` (but with the path relative to the crate root),
+and reports errors with the line and column in the file:
+
+```ignore
+use hexhex::include_hex;
+let firmware: &[u8; 1024] = include_hex!("firmware/blob.hex");
+```
+
 The macro is a proc-macro, not a declarative macro; it can be used in a `match` arm:
 
 ```
@@ -103,18 +375,75 @@ match x {
 }
 ```
 
+## Const decoding
+
+[`decode_array`] is a `const fn`, so it can initialize `const` and `static` items without the macro.
+Malformed hex strings cause a compile-time error there, too:
+
+```
+use hexhex::decode_array;
+const FIRMWARE_ID: [u8; 4] = decode_array("0xc0ffee00");
+assert_eq!(FIRMWARE_ID, [0xc0, 0xff, 0xee, 0x00]);
+```
+
+[`try_decode_array`] returns a `Result` instead.
+
 ## Feature flags
 
 - `std` (enabled by default): Enables functionality that makes use of `std`. With this flag disabled, the crate is `#![no_std]` compatible.
+- `miette`: Implements [`miette::Diagnostic`](https://docs.rs/miette/latest/miette/trait.Diagnostic.html) for [`FromHexError`], labelling the error position. Implies `std`.
+- `bytes`: Implements [`DecodeSink`] for `bytes::BytesMut`, and adds `BufMutSink` for any `bytes::BufMut`.
+- `heapless`: Implements [`DecodeSink`] for `heapless::Vec<u8, N>`.
+- `arrayvec`: Implements [`DecodeSink`] for `arrayvec::ArrayVec<u8, N>`.
+- `zeroize`: Adds decode functions returning [`zeroize::Zeroizing`](https://docs.rs/zeroize/latest/zeroize/struct.Zeroizing.html) buffers.
+  Formatting with [`Hex`] does not wipe anything, see [Secrets](#secrets).
 
 */
 
 #![forbid(unsafe_code)]
 
 #[cfg(feature = "std")]
-pub use hexhex_impl::{decode, decode_ascii};
 pub use hexhex_impl::{
-    decode_ascii_to_buf, decode_ascii_to_buf_exact, decode_to_buf, decode_to_buf_exact, hex, Case,
-    FromHexError, Hex,
+    decode, decode_ascii, decode_ascii_with_options, decode_dump, decode_with_options,
+};
+pub use hexhex_impl::{
+    decode_array, decode_ascii_into, decode_ascii_into_with_options, decode_ascii_iter,
+    decode_ascii_iter_with_options, decode_ascii_nibbles, decode_ascii_nibbles_with_options,
+    decode_ascii_to_buf, decode_ascii_to_buf_exact, decode_ascii_to_buf_exact_with_options,
+    decode_ascii_to_buf_with_options, decode_ascii_words, decode_ascii_words_with_options,
+    decode_in_place, decode_in_place_with_options, decode_into, decode_into_with_options,
+    decode_iter, decode_iter_with_options, decode_nibbles, decode_nibbles_with_options,
+    decode_to_buf, decode_to_buf_exact, decode_to_buf_exact_with_options,
+    decode_to_buf_with_options, decode_words, decode_words_with_options, decoded_len, encode_array,
+    encode_in_place, encoded_len, encoded_str, hex, hex_words, try_decode_array, validate,
+    validate_all, validate_all_with_callback, validate_all_with_options, validate_ascii,
+    validate_ascii_with_options, validate_with_options, Case, CasePolicy, DecodeOptions,
+    DecodeSink, DumpDecodeIterator, Endian, FromDumpError, FromDumpErrorKind, FromHexError,
+    FromHexErrorKind, Hex, HexWords, LineError, LineErrorKind, NibbleIter, NibbleOrder, Nibbles,
+    OddLengthPolicy, PrefixPolicy, Snippet, Word,
 };
 pub use hexhex_macros::*;
+
+#[cfg(feature = "bytes")]
+pub use hexhex_impl::BufMutSink;
+
+#[cfg(feature = "std")]
+pub use hexhex_impl::MemoryImage;
+#[cfg(feature = "std")]
+pub use hexhex_impl::{
+    decode_ascii_words_vec, decode_ascii_words_vec_with_options, decode_words_vec,
+    decode_words_vec_with_options,
+};
+
+#[cfg(all(feature = "std", feature = "zeroize"))]
+pub use hexhex_impl::{
+    decode_ascii_zeroizing, decode_ascii_zeroizing_with_options, decode_zeroizing,
+    decode_zeroizing_with_options,
+};
+#[cfg(feature = "zeroize")]
+pub use hexhex_impl::{
+    decode_in_place_zeroizing, decode_in_place_zeroizing_with_options, try_decode_array_zeroizing,
+    try_decode_ascii_array_zeroizing, ZeroizingInput,
+};
+
+pub use hexhex_impl::{ct, float, ihex, readmemh, srec};
@@ -0,0 +1,107 @@
+//! Constant-time encoding and decoding, for secret material like keys and MAC tags
+//!
+//! The regular codec branches on the data and uses lookup tables, which can leak it through timing.
+//! The functions here compute every digit and byte arithmetically instead, in the style of the
+//! constant-time base16 codecs for RFC 4648, so that their running time depends only on the
+//! length of the input. Only lengths are checked up front.
+//!
+//! Because of that, decoding cannot stop at the first invalid digit or say where it is:
+//! the whole input is decoded, and errors are reported as a single validity flag at the end.
+//! Only plain hex digits of either case are accepted, without prefix or separators.
+//!
+//! This is best effort: Rust gives no guarantee that the compiler keeps the code branch-free.
+//!
+//! # Example
+//!
+//! ```
+//! use hexhex_impl::{ct, Case};
+//! let key = [0xde, 0xad, 0xbe, 0xef];
+//! let mut hex = [0u8; 8];
+//! ct::encode(&key, &mut hex, Case::Lower).unwrap();
+//! assert_eq!(&hex, b"deadbeef");
+//!
+//! let mut decoded = [0u8; 4];
+//! ct::decode_ascii(&hex, &mut decoded).unwrap();
+//! assert_eq!(decoded, key);
+//! assert!(ct::decode("deadbeeg", &mut decoded).is_err());
+//! ```
+
+use crate::Case;
+
+/// The input is not valid hex, or the output buffer does not have the right length
+///
+/// Deliberately carries no details, since the position of an invalid digit could reveal secret data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Error;
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "invalid hex or buffer length")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+/// The ASCII digit for a nibble (`0..16`); `alpha` is the distance from `'9' + 1` to `'a'` or `'A'`
+fn encode_nibble(nibble: u8, alpha: u16) -> u8 {
+    let nibble = u16::from(nibble);
+    // 9 - nibble is negative (so its high byte is all ones) exactly for the letters
+    (nibble + u16::from(b'0') + ((9u16.wrapping_sub(nibble) >> 8) & alpha)) as u8
+}
+
+/// The value of an ASCII hex digit, or a value with the high byte set if it is none
+fn decode_nibble(c: u8) -> u16 {
+    let c = i16::from(c);
+    // each term is c - offset if c is in the range (the mask is all ones), zero otherwise
+    let digit = (((0x2f - c) & (c - 0x3a)) >> 8) & (c - 0x2f);
+    let upper = (((0x40 - c) & (c - 0x47)) >> 8) & (c - 0x36);
+    let lower = (((0x60 - c) & (c - 0x67)) >> 8) & (c - 0x56);
+    // -1 unless one of the ranges matched
+    (-1 + digit + upper + lower) as u16
+}
+
+/// Encode `bytes` as hex into `dst`, which must be exactly twice as long.
+///
+/// Use [`encoded_str`](crate::encoded_str) to view the result as a `&str`, keeping in mind that
+/// UTF-8 validation is not constant-time.
+pub fn encode(bytes: &[u8], dst: &mut [u8], case: Case) -> Result<(), Error> {
+    if dst.len() != 2 * bytes.len() {
+        return Err(Error);
+    }
+    let alpha = match case {
+        Case::Lower => u16::from(b'a' - b'9' - 1),
+        Case::Upper => u16::from(b'A' - b'9' - 1),
+    };
+    for (byte, digits) in bytes.iter().zip(dst.chunks_exact_mut(2)) {
+        digits[0] = encode_nibble(byte >> 4, alpha);
+        digits[1] = encode_nibble(byte & 0xf, alpha);
+    }
+    Ok(())
+}
+
+/// Decode a hex string into `dst`, which must be exactly half as long.
+///
+/// On error, `dst` holds garbage and should be discarded.
+pub fn decode(hex: &str, dst: &mut [u8]) -> Result<(), Error> {
+    decode_ascii(hex.as_bytes(), dst)
+}
+
+/// Like [`decode`], but for a byte string.
+pub fn decode_ascii(hex: &[u8], dst: &mut [u8]) -> Result<(), Error> {
+    if hex.len() != 2 * dst.len() {
+        return Err(Error);
+    }
+    let mut invalid = 0;
+    for (digits, byte) in hex.chunks_exact(2).zip(dst.iter_mut()) {
+        let high = decode_nibble(digits[0]);
+        let low = decode_nibble(digits[1]);
+        invalid |= (high | low) >> 8;
+        *byte = ((high << 4) | low) as u8;
+    }
+    if invalid == 0 {
+        Ok(())
+    } else {
+        Err(Error)
+    }
+}
@@ -0,0 +1,180 @@
+use super::{FromHexError, FromHexErrorKind};
+
+const fn nibble(byte: u8) -> Option<u8> {
+    match byte {
+        b'0'..=b'9' => Some(byte - b'0'),
+        b'a'..=b'f' => Some(byte - b'a' + 10),
+        b'A'..=b'F' => Some(byte - b'A' + 10),
+        _ => None,
+    }
+}
+
+/// The char starting at the given position of valid UTF-8 (`str::chars` is not const)
+const fn char_at(bytes: &[u8], position: usize) -> char {
+    let first = bytes[position] as u32;
+    let (len, mut code) = match first {
+        0x00..=0x7f => (1, first),
+        0x80..=0xdf => (2, first & 0x1f),
+        0xe0..=0xef => (3, first & 0x0f),
+        _ => (4, first & 0x07),
+    };
+    let mut i = 1;
+    while i < len {
+        code = code << 6 | (bytes[position + i] as u32 & 0x3f);
+        i += 1;
+    }
+    match char::from_u32(code) {
+        Some(c) => c,
+        None => char::REPLACEMENT_CHARACTER,
+    }
+}
+
+const fn nibble_at(bytes: &[u8], position: usize) -> Result<u8, FromHexError> {
+    match nibble(bytes[position]) {
+        Some(nibble) => Ok(nibble),
+        None => Err(FromHexError {
+            position,
+            kind: FromHexErrorKind::UnexpectedCharacter(char_at(bytes, position)),
+        }),
+    }
+}
+
+/// Decode a hex string into an array of exactly `N` bytes, in a const context if needed.
+///
+/// Accepts lower case, upper case, and mixed case hex characters a-f.
+/// Strips leading `0x` if present.
+/// Fails like [`decode_to_buf_exact`](crate::decode_to_buf_exact) if the string does not consist of exactly `2 * N` hex digits.
+///
+/// # Example
+///
+/// ```
+/// use hexhex_impl::*;
+/// const ID: Result<[u8; 2], FromHexError> = try_decode_array("0xc0de");
+/// assert_eq!(ID.unwrap(), [0xc0, 0xde]);
+/// assert!(try_decode_array::<2>("c0d").is_err());
+/// assert!(try_decode_array::<2>("c0dex").is_err());
+/// ```
+pub const fn try_decode_array<const N: usize>(hex: &str) -> Result<[u8; N], FromHexError> {
+    let bytes = hex.as_bytes();
+    let mut position = if bytes.len() >= 2 && bytes[0] == b'0' && bytes[1] == b'x' {
+        2
+    } else {
+        0
+    };
+    let mut array = [0u8; N];
+    let mut len = 0;
+    // position of the first byte that does not fit into the array
+    let mut too_small = None;
+    while position < bytes.len() {
+        let high = match nibble_at(bytes, position) {
+            Ok(nibble) => nibble,
+            Err(e) => return Err(e),
+        };
+        if position + 1 == bytes.len() {
+            return Err(FromHexError {
+                position,
+                kind: FromHexErrorKind::OddLength,
+            });
+        }
+        let low = match nibble_at(bytes, position + 1) {
+            Ok(nibble) => nibble,
+            Err(e) => return Err(e),
+        };
+        if len < N {
+            array[len] = high << 4 | low;
+        } else if too_small.is_none() {
+            too_small = Some(position);
+        }
+        len += 1;
+        position += 2;
+    }
+    if let Some(position) = too_small {
+        return Err(FromHexError {
+            position,
+            kind: FromHexErrorKind::OutputTooSmall { required: len },
+        });
+    }
+    if len < N {
+        return Err(FromHexError {
+            position: bytes.len(),
+            kind: FromHexErrorKind::OutputTooLarge,
+        });
+    }
+    Ok(array)
+}
+
+/// Decode a hex string into an array of exactly `N` bytes, for `const` and `static` items.
+///
+/// Accepts the same input as [`try_decode_array`], and panics if it fails,
+/// which is a compile time error in a const context.
+/// Unlike `hex_literal!`, this works without the proc-macro crate.
+///
+/// # Example
+///
+/// ```
+/// use hexhex_impl::decode_array;
+/// const KEY: [u8; 4] = decode_array("0xdeadbeef");
+/// static MAGIC: [u8; 2] = decode_array("CAFE");
+/// assert_eq!(KEY, [0xde, 0xad, 0xbe, 0xef]);
+/// assert_eq!(MAGIC, [0xca, 0xfe]);
+/// ```
+///
+/// Invalid input does not compile:
+///
+/// ```compile_fail
+/// use hexhex_impl::decode_array;
+/// const KEY: [u8; 4] = decode_array("0xdeadbeex");
+/// # let _ = KEY;
+/// ```
+pub const fn decode_array<const N: usize>(hex: &str) -> [u8; N] {
+    match try_decode_array(hex) {
+        Ok(array) => array,
+        Err(FromHexError {
+            kind: FromHexErrorKind::UnexpectedCharacter(_),
+            ..
+        }) => panic!("invalid hex string: unexpected character"),
+        Err(FromHexError {
+            kind: FromHexErrorKind::OddLength,
+            ..
+        }) => panic!("invalid hex string: odd number of hex digits"),
+        Err(FromHexError {
+            kind: FromHexErrorKind::OutputTooSmall { .. },
+            ..
+        }) => panic!("invalid hex string: too long for the array"),
+        Err(FromHexError {
+            kind: FromHexErrorKind::OutputTooLarge,
+            ..
+        }) => panic!("invalid hex string: too short for the array"),
+        Err(_) => panic!("invalid hex string"),
+    }
+}
+
+/// Like [`try_decode_array`], but the bytes are wiped from memory when dropped, and on error.
+///
+/// Not a `const fn`; secrets should not be constants anyway.
+///
+/// # Example
+///
+/// ```
+/// use hexhex_impl::*;
+/// let key = try_decode_array_zeroizing::<2>("0xc0de").unwrap();
+/// assert_eq!(*key, [0xc0, 0xde]);
+/// ```
+#[cfg(feature = "zeroize")]
+pub fn try_decode_array_zeroizing<const N: usize>(
+    hex: &str,
+) -> Result<zeroize::Zeroizing<[u8; N]>, FromHexError> {
+    let mut bytes = zeroize::Zeroizing::new([0u8; N]);
+    crate::decode_to_buf_exact(hex, &mut *bytes)?;
+    Ok(bytes)
+}
+
+/// Like [`try_decode_array_zeroizing`], but for a byte string.
+#[cfg(feature = "zeroize")]
+pub fn try_decode_ascii_array_zeroizing<const N: usize>(
+    hex: &[u8],
+) -> Result<zeroize::Zeroizing<[u8; N]>, FromHexError> {
+    let mut bytes = zeroize::Zeroizing::new([0u8; N]);
+    crate::decode_ascii_to_buf_exact(hex, &mut *bytes)?;
+    Ok(bytes)
+}
@@ -1,7 +1,7 @@
 use fallible_iterator::FallibleIterator;
 
-use super::{FromHexError, FromHexErrorKind};
-use crate::decode::common::{has_0x_prefix, has_0x_prefix_ascii};
+use super::common::needs_padding;
+use super::{DecodeOptions, FromHexError, FromHexErrorKind};
 use crate::decode::streaming::HexDecodeAsciiIterator;
 
 #[derive(Debug, PartialEq, Eq)]
@@ -14,6 +14,7 @@ fn decode_ascii_to_buf_internal<I: FallibleIterator<Item = (usize, u8), Error =
     mut iter: HexDecodeAsciiIterator<I>,
     dst: &mut [u8],
     output_length: OutputLength,
+    input_length: usize,
 ) -> Result<usize, FromHexError> {
     let mut off_dst = 0;
     loop {
@@ -24,17 +25,20 @@ fn decode_ascii_to_buf_internal<I: FallibleIterator<Item = (usize, u8), Error =
                     off_dst += 1;
                 }
                 None => {
+                    let position = iter.byte_position();
+                    // the rest of the input must still be valid to tell how much space it needs
+                    let required = off_dst + 1 + iter.count()?;
                     return Err(FromHexError {
-                        position: iter.last_position(),
-                        kind: FromHexErrorKind::Eof,
+                        position,
+                        kind: FromHexErrorKind::OutputTooSmall { required },
                     });
                 }
             },
             Ok(None) => {
                 if output_length == OutputLength::MustEqualDst && off_dst < dst.len() {
                     return Err(FromHexError {
-                        position: iter.last_position(),
-                        kind: FromHexErrorKind::OutputBufferTooShort,
+                        position: input_length,
+                        kind: FromHexErrorKind::OutputTooLarge,
                     });
                 } else {
                     return Ok(off_dst);
@@ -47,28 +51,51 @@ fn decode_ascii_to_buf_internal<I: FallibleIterator<Item = (usize, u8), Error =
     }
 }
 
+fn decode_ascii_to_buf_with_length(
+    hex: &[u8],
+    dst: &mut [u8],
+    options: DecodeOptions,
+    output_length: OutputLength,
+) -> Result<usize, FromHexError> {
+    decode_ascii_to_buf_internal(
+        HexDecodeAsciiIterator::new_with_options(
+            fallible_iterator::convert(hex.iter().copied().enumerate().map(Ok)),
+            options,
+        )
+        .with_padding(needs_padding(hex.iter().map(|&b| b as char), &options)),
+        dst,
+        output_length,
+        hex.len(),
+    )
+}
+
 fn decode_to_buf_internal(
     hex: &str,
     dst: &mut [u8],
+    options: DecodeOptions,
     output_length: OutputLength,
 ) -> Result<usize, FromHexError> {
     // iterate over chars
     // convert each char to ASCII u8 (< 128), if possible; otherwise fail
-    let chars_as_bytes_with_position = hex
-        .char_indices()
-        .map(|(position, c)| match u8::try_from(c) {
-            Ok(byte) if byte < 128 => Ok((position, byte)),
-            _ => Err(FromHexError {
-                position,
-                kind: FromHexErrorKind::UnexpectedCharacter(c),
-            }),
-        })
-        .skip(if has_0x_prefix(hex) { 2 } else { 0 });
+    let chars_as_bytes_with_position =
+        hex.char_indices()
+            .map(|(position, c)| match u8::try_from(c) {
+                Ok(byte) if byte < 128 => Ok((position, byte)),
+                _ => Err(FromHexError {
+                    position,
+                    kind: FromHexErrorKind::UnexpectedCharacter(c),
+                }),
+            });
     // use the ASCII decode function, writing to dst
     let result = decode_ascii_to_buf_internal(
-        HexDecodeAsciiIterator::new(fallible_iterator::convert(chars_as_bytes_with_position)),
+        HexDecodeAsciiIterator::new_with_options(
+            fallible_iterator::convert(chars_as_bytes_with_position),
+            options,
+        )
+        .with_padding(needs_padding(hex.chars(), &options)),
         dst,
         output_length,
+        hex.len(),
     );
     // if there is an error, make sure we refer to the char, not its first byte
     match result {
@@ -102,17 +129,26 @@ fn decode_to_buf_internal(
 /// assert_eq!(&output[..2], &[0x12, 0x34]);
 /// ```
 pub fn decode_ascii_to_buf(hex: &[u8], dst: &mut [u8]) -> Result<usize, FromHexError> {
-    decode_ascii_to_buf_internal(
-        HexDecodeAsciiIterator::new(fallible_iterator::convert(
-            hex.iter()
-                .copied()
-                .enumerate()
-                .skip(if has_0x_prefix_ascii(hex) { 2 } else { 0 })
-                .map(Ok),
-        )),
-        dst,
-        OutputLength::MayBeShorterThanDst,
-    )
+    decode_ascii_to_buf_with_options(hex, dst, DecodeOptions::default())
+}
+
+/// Like [`decode_ascii_to_buf`], but with the given options.
+///
+/// # Example
+///
+/// ```
+/// use hexhex_impl::*;
+/// let options = DecodeOptions::new().with_separators(" ");
+/// let mut output = [0u8; 4];
+/// assert_eq!(decode_ascii_to_buf_with_options(b"12 34", &mut output, options).unwrap(), 2);
+/// assert_eq!(&output[..2], &[0x12, 0x34]);
+/// ```
+pub fn decode_ascii_to_buf_with_options(
+    hex: &[u8],
+    dst: &mut [u8],
+    options: DecodeOptions,
+) -> Result<usize, FromHexError> {
+    decode_ascii_to_buf_with_length(hex, dst, options, OutputLength::MayBeShorterThanDst)
 }
 
 /// Like [`decode_ascii_to_buf`], but returns an error if not all of dst has been overwritten.
@@ -134,18 +170,26 @@ pub fn decode_ascii_to_buf(hex: &[u8], dst: &mut [u8]) -> Result<usize, FromHexE
 /// assert!(decode_ascii_to_buf_exact(input, &mut output).is_err());
 /// ```
 pub fn decode_ascii_to_buf_exact(hex: &[u8], dst: &mut [u8]) -> Result<(), FromHexError> {
-    decode_ascii_to_buf_internal(
-        HexDecodeAsciiIterator::new(fallible_iterator::convert(
-            hex.iter()
-                .copied()
-                .enumerate()
-                .skip(if has_0x_prefix_ascii(hex) { 2 } else { 0 })
-                .map(Ok),
-        )),
-        dst,
-        OutputLength::MustEqualDst,
-    )
-    .map(|_| ())
+    decode_ascii_to_buf_exact_with_options(hex, dst, DecodeOptions::default())
+}
+
+/// Like [`decode_ascii_to_buf_exact`], but with the given options.
+///
+/// # Example
+///
+/// ```
+/// use hexhex_impl::*;
+/// let options = DecodeOptions::new().with_prefix(PrefixPolicy::Required);
+/// let mut output = [0u8; 3];
+/// assert!(decode_ascii_to_buf_exact_with_options(b"0xc0ffee", &mut output, options).is_ok());
+/// assert!(decode_ascii_to_buf_exact_with_options(b"c0ffee", &mut output, options).is_err());
+/// ```
+pub fn decode_ascii_to_buf_exact_with_options(
+    hex: &[u8],
+    dst: &mut [u8],
+    options: DecodeOptions,
+) -> Result<(), FromHexError> {
+    decode_ascii_to_buf_with_length(hex, dst, options, OutputLength::MustEqualDst).map(|_| ())
 }
 
 /// Decode the given hex string and write the corresponding bytes to dst.
@@ -166,7 +210,26 @@ pub fn decode_ascii_to_buf_exact(hex: &[u8], dst: &mut [u8]) -> Result<(), FromH
 /// assert_eq!(&output[..2], &[0x12, 0x34]);
 /// ```
 pub fn decode_to_buf(hex: &str, dst: &mut [u8]) -> Result<usize, FromHexError> {
-    decode_to_buf_internal(hex, dst, OutputLength::MayBeShorterThanDst)
+    decode_to_buf_with_options(hex, dst, DecodeOptions::default())
+}
+
+/// Like [`decode_to_buf`], but with the given options.
+///
+/// # Example
+///
+/// ```
+/// use hexhex_impl::*;
+/// let options = DecodeOptions::new().with_case(CasePolicy::Consistent);
+/// let mut output = [0u8; 4];
+/// assert_eq!(decode_to_buf_with_options("ABCD", &mut output, options).unwrap(), 2);
+/// assert!(decode_to_buf_with_options("ABcd", &mut output, options).is_err());
+/// ```
+pub fn decode_to_buf_with_options(
+    hex: &str,
+    dst: &mut [u8],
+    options: DecodeOptions,
+) -> Result<usize, FromHexError> {
+    decode_to_buf_internal(hex, dst, options, OutputLength::MayBeShorterThanDst)
 }
 
 /// Like [`decode_to_buf`], but returns an error if not all of dst has been overwritten.
@@ -188,5 +251,24 @@ pub fn decode_to_buf(hex: &str, dst: &mut [u8]) -> Result<usize, FromHexError> {
 /// assert!(decode_to_buf_exact(input, &mut output).is_err());
 /// ```
 pub fn decode_to_buf_exact(hex: &str, dst: &mut [u8]) -> Result<(), FromHexError> {
-    decode_to_buf_internal(hex, dst, OutputLength::MustEqualDst).map(|_| ())
+    decode_to_buf_exact_with_options(hex, dst, DecodeOptions::default())
+}
+
+/// Like [`decode_to_buf_exact`], but with the given options.
+///
+/// # Example
+///
+/// ```
+/// use hexhex_impl::*;
+/// let options = DecodeOptions::new().with_separators(":");
+/// let mut output = [0u8; 3];
+/// assert!(decode_to_buf_exact_with_options("c0:ff:ee", &mut output, options).is_ok());
+/// assert_eq!(output, [0xc0, 0xff, 0xee]);
+/// ```
+pub fn decode_to_buf_exact_with_options(
+    hex: &str,
+    dst: &mut [u8],
+    options: DecodeOptions,
+) -> Result<(), FromHexError> {
+    decode_to_buf_internal(hex, dst, options, OutputLength::MustEqualDst).map(|_| ())
 }
@@ -1,12 +1,24 @@
-use super::{FromHexError, FromHexErrorKind};
+use fallible_iterator::FallibleIterator;
+
+use super::{
+    CasePolicy, DecodeOptions, FromHexError, FromHexErrorKind, OddLengthPolicy, PrefixPolicy,
+};
+use crate::encode::Case;
 
 impl core::fmt::Display for FromHexErrorKind {
     fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         match self {
             FromHexErrorKind::UnexpectedCharacter(c) => write!(f, "unexpected character {c}"),
             FromHexErrorKind::UnexpectedByte(b) => write!(f, "unexpected byte 0x{b:02x}"),
-            FromHexErrorKind::Eof => write!(f, "unexpected end of input"),
-            FromHexErrorKind::OutputBufferTooShort => write!(f, "output buffer is too short"),
+            FromHexErrorKind::OddLength => write!(f, "odd number of hex digits"),
+            FromHexErrorKind::InvalidPrefix => write!(f, "invalid prefix"),
+            FromHexErrorKind::OutputTooSmall { required } => {
+                write!(f, "output buffer is too small, {required} bytes required")
+            }
+            FromHexErrorKind::OutputTooLarge => write!(f, "output buffer is too large"),
+            FromHexErrorKind::Overflow => write!(f, "number too large"),
+            FromHexErrorKind::MissingDigits => write!(f, "missing digits"),
+            FromHexErrorKind::IncompleteWord => write!(f, "incomplete word"),
         }
     }
 }
@@ -44,10 +56,228 @@ pub fn strip_0x_ascii(buf: &[u8]) -> Option<&[u8]> {
 }
 */
 
-pub fn has_0x_prefix(s: &str) -> bool {
-    s.starts_with("0x")
+/// Decoding state shared by the streaming iterators (and, through them, all other decode functions)
+///
+/// Takes `(position, byte)` pairs and produces one decoded byte at a time, applying the [`DecodeOptions`].
+#[derive(Debug, Clone)]
+pub(crate) struct Decoder {
+    options: DecodeOptions,
+    prefix_checked: bool,
+    pad: bool,
+    /// Items read ahead while checking the prefix (`Some(None)` means end of input)
+    pending: [Option<Option<(usize, u8)>>; 2],
+    case_seen: Option<Case>,
+    byte_position: usize,
+    /// Position of an invalid first digit, whose second digit must be skipped when resuming after the error
+    resume_low: Option<usize>,
+}
+
+impl Decoder {
+    pub fn new(options: DecodeOptions) -> Self {
+        Self {
+            options,
+            prefix_checked: false,
+            pad: false,
+            pending: [None, None],
+            case_seen: None,
+            byte_position: 0,
+            resume_low: None,
+        }
+    }
+
+    /// Treat the first digit as the low nibble of the first byte (if `pad` is true)
+    pub fn set_pad(&mut self, pad: bool) {
+        self.pad = pad;
+    }
+
+    /// Position of the first digit of the last decoded byte
+    pub fn byte_position(&self) -> usize {
+        self.byte_position
+    }
+
+    /// Decode the next byte.
+    ///
+    /// Decoding can resume after an error, as if each invalid character were a valid digit
+    /// (and an invalid prefix were fixed), so that all errors in the input can be found.
+    pub fn next<I>(&mut self, iter: &mut I) -> Result<Option<u8>, FromHexError>
+    where
+        I: FallibleIterator<Item = (usize, u8), Error = FromHexError>,
+    {
+        if !self.prefix_checked {
+            self.prefix_checked = true;
+            self.check_prefix(iter)?;
+        }
+        if let Some(high_position) = self.resume_low.take() {
+            let second = self.next_item(iter)?;
+            self.low_nibble(high_position, 0, second)?;
+        }
+        // read first digit, skipping separators
+        let first = loop {
+            match self.next_item(iter) {
+                Ok(None) => return Ok(None),
+                Ok(Some((_, c))) if self.options.is_separator(c) => {}
+                Ok(Some((position, c))) => {
+                    break self.digit(position, c).map(|high| (position, high))
+                }
+                Err(err) => break Err(err),
+            }
+        };
+        let pad = core::mem::take(&mut self.pad);
+        let (position, high) = match first {
+            Ok(digit) => digit,
+            Err(err) => {
+                if !pad {
+                    self.resume_low = Some(err.position);
+                }
+                return Err(err);
+            }
+        };
+        self.byte_position = position;
+        if pad {
+            // implicit leading zero: the first digit is already a full byte
+            return Ok(Some(high));
+        }
+        // read second digit
+        let second = self.next_item(iter)?;
+        self.low_nibble(position, high, second).map(Some)
+    }
+
+    fn next_item<I>(&mut self, iter: &mut I) -> Result<Option<(usize, u8)>, FromHexError>
+    where
+        I: FallibleIterator<Item = (usize, u8), Error = FromHexError>,
+    {
+        match self.pending[0].take() {
+            Some(item) => {
+                self.pending.swap(0, 1);
+                Ok(item)
+            }
+            None => iter.next(),
+        }
+    }
+
+    /// Consume the prefix (or check its absence) according to the prefix policy.
+    /// Anything that turns out not to be a prefix is kept for later.
+    fn check_prefix<I>(&mut self, iter: &mut I) -> Result<(), FromHexError>
+    where
+        I: FallibleIterator<Item = (usize, u8), Error = FromHexError>,
+    {
+        let first = iter.next()?;
+        // only read ahead if the first item may start a prefix
+        let second = match first {
+            Some((_, b'0')) => Some(iter.next()?),
+            _ => None,
+        };
+        let found = has_prefix(
+            [first, second.flatten()]
+                .into_iter()
+                .map_while(|item| item.map(|(_, c)| c as char)),
+        );
+        if !found {
+            // not a prefix, so these are the first items of the input
+            self.pending = [Some(first), second];
+        }
+        // the forbidden prefix is consumed, so decoding can resume after it
+        check_prefix_policy(found, self.options.prefix)
+            .map_err(|_| invalid_prefix(first.map_or(0, |(position, _)| position)))
+    }
+
+    fn low_nibble(
+        &mut self,
+        high_position: usize,
+        high: u8,
+        item: Option<(usize, u8)>,
+    ) -> Result<u8, FromHexError> {
+        let Some((position, c)) = item else {
+            return Err(FromHexError {
+                position: high_position,
+                kind: FromHexErrorKind::OddLength,
+            });
+        };
+        let low = self.digit(position, c)?;
+        // Got two hex digits, done
+        Ok(high * 16 + low)
+    }
+
+    fn digit(&mut self, position: usize, c: u8) -> Result<u8, FromHexError> {
+        let (value, case) = match c {
+            b'0'..=b'9' => (c - b'0', None),
+            b'a'..=b'f' => (c - b'a' + 10, Some(Case::Lower)),
+            b'A'..=b'F' => (c - b'A' + 10, Some(Case::Upper)),
+            _ => return Err(unexpected_byte(position, c)),
+        };
+        if let Some(case) = case {
+            let accepted = match self.options.case {
+                CasePolicy::Any => true,
+                CasePolicy::Lower => case == Case::Lower,
+                CasePolicy::Upper => case == Case::Upper,
+                CasePolicy::Consistent => *self.case_seen.get_or_insert(case) == case,
+            };
+            if !accepted {
+                return Err(unexpected_byte(position, c));
+            }
+        }
+        Ok(value)
+    }
+}
+
+fn unexpected_byte(position: usize, byte: u8) -> FromHexError {
+    FromHexError {
+        position,
+        kind: FromHexErrorKind::UnexpectedByte(byte),
+    }
+}
+
+fn invalid_prefix(position: usize) -> FromHexError {
+    FromHexError {
+        position,
+        kind: FromHexErrorKind::InvalidPrefix,
+    }
+}
+
+/// Whether the input starts with the `0x` prefix
+///
+/// This is the one place that defines the prefix, for the decoder as well as for input that is not decoded through it.
+pub(crate) fn has_prefix(mut hex: impl Iterator<Item = char>) -> bool {
+    hex.next() == Some('0') && hex.next() == Some('x')
+}
+
+/// Check whether the presence or absence of the prefix is allowed by the prefix policy
+pub(crate) fn check_prefix_policy(
+    found: bool,
+    policy: PrefixPolicy,
+) -> Result<(), FromHexErrorKind> {
+    match (policy, found) {
+        (PrefixPolicy::Required, false) | (PrefixPolicy::Forbidden, true) => {
+            Err(FromHexErrorKind::InvalidPrefix)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Whether the input needs an implicit leading zero digit according to the odd length policy
+pub(crate) fn needs_padding(
+    hex: impl Iterator<Item = char> + Clone,
+    options: &DecodeOptions,
+) -> bool {
+    options.odd_length == OddLengthPolicy::PadLeft && digit_count(hex, options) % 2 == 1
+}
+
+/// The number of characters after the prefix (if any) that are not separators
+pub(crate) fn digit_count(
+    hex: impl Iterator<Item = char> + Clone,
+    options: &DecodeOptions,
+) -> usize {
+    let skip = if options.prefix != PrefixPolicy::Forbidden && has_prefix(hex.clone()) {
+        2
+    } else {
+        0
+    };
+    hex.skip(skip)
+        .filter(|&c| !(c.is_ascii() && options.is_separator(c as u8)))
+        .count()
 }
 
-pub fn has_0x_prefix_ascii(buf: &[u8]) -> bool {
-    buf.starts_with(b"0x")
+/// 1-based column (in chars) of the given byte position within a line
+pub(crate) fn column(line: &str, position: usize) -> usize {
+    line.get(..position).map_or(position, |s| s.chars().count()) + 1
 }
@@ -0,0 +1,120 @@
+use super::common::column;
+use super::FromHexError;
+
+/// The line containing the given byte position, and the position of its first byte
+fn line_at(input: &str, position: usize) -> (&str, usize) {
+    let mut position = position.min(input.len());
+    while !input.is_char_boundary(position) {
+        position -= 1;
+    }
+    let start = input[..position].rfind('\n').map_or(0, |i| i + 1);
+    let end = input[position..]
+        .find('\n')
+        .map_or(input.len(), |i| position + i);
+    (&input[start..end], start)
+}
+
+impl FromHexError {
+    /// The 1-based line number and column (in chars) of the error within `input`,
+    /// which must be the string that failed to decode.
+    ///
+    /// Lines are separated by `\n`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexhex_impl::*;
+    /// let input = "c0ffee\nc0fxee\n";
+    /// let mut buf = [0u8; 6];
+    /// let options = DecodeOptions::new().with_separators("\n");
+    /// let err = decode_to_buf_with_options(input, &mut buf, options).unwrap_err();
+    /// assert_eq!(err.line_column(input), (2, 4));
+    /// ```
+    pub fn line_column(&self, input: &str) -> (usize, usize) {
+        let (line, start) = line_at(input, self.position);
+        let number = input[..start].matches('\n').count() + 1;
+        (number, column(line, self.position - start))
+    }
+
+    /// Render the error with the offending line of `input` and a caret under the error position.
+    ///
+    /// `input` must be the string that failed to decode. Nothing is computed until the snippet is displayed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexhex_impl::*;
+    /// let input = "c0ffee\nc0fxee\n";
+    /// let mut buf = [0u8; 6];
+    /// let options = DecodeOptions::new().with_separators("\n");
+    /// let err = decode_to_buf_with_options(input, &mut buf, options).unwrap_err();
+    /// assert_eq!(
+    ///     err.snippet(input).to_string(),
+    ///     "\
+    /// error converting from hex: unexpected character x at line 2, column 4
+    ///   |
+    /// 2 | c0fxee
+    ///   |    ^
+    /// "
+    /// );
+    /// ```
+    pub fn snippet<'a>(&self, input: &'a str) -> Snippet<'a> {
+        Snippet {
+            error: *self,
+            input,
+        }
+    }
+}
+
+/// A [`FromHexError`] together with the input that failed to decode, displayed as a snippet
+///
+/// Created by [`FromHexError::snippet`].
+#[derive(Debug, Clone, Copy)]
+pub struct Snippet<'a> {
+    error: FromHexError,
+    input: &'a str,
+}
+
+impl core::fmt::Display for Snippet<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        let (line, start) = line_at(self.input, self.error.position);
+        let (number, column) = self.error.line_column(self.input);
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        let width = number.ilog10() as usize + 1;
+        writeln!(
+            f,
+            "error converting from hex: {} at line {number}, column {column}",
+            self.error.kind
+        )?;
+        writeln!(f, "{:width$} |", "")?;
+        writeln!(f, "{number} | {line}")?;
+        write!(f, "{:width$} | ", "")?;
+        // keep tabs so that the caret lines up
+        let before = line.get(..self.error.position - start).unwrap_or(line);
+        for c in before.chars() {
+            write!(f, "{}", if c == '\t' { '\t' } else { ' ' })?;
+        }
+        writeln!(f, "^")
+    }
+}
+
+/// Labels the error position, so that `miette` can show it within the source code
+/// attached with [`miette::Report::with_source_code`].
+#[cfg(feature = "miette")]
+impl miette::Diagnostic for FromHexError {
+    fn code<'a>(&'a self) -> Option<std::boxed::Box<dyn core::fmt::Display + 'a>> {
+        Some(std::boxed::Box::new("hexhex::from_hex"))
+    }
+
+    fn labels(&self) -> Option<std::boxed::Box<dyn Iterator<Item = miette::LabeledSpan> + '_>> {
+        let len = match self.kind {
+            super::FromHexErrorKind::UnexpectedCharacter(c) => c.len_utf8(),
+            super::FromHexErrorKind::UnexpectedByte(_) => 1,
+            _ => 0,
+        };
+        let label = std::string::ToString::to_string(&self.kind);
+        Some(std::boxed::Box::new(core::iter::once(
+            miette::LabeledSpan::new(Some(label), self.position, len),
+        )))
+    }
+}
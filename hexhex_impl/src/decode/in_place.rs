@@ -0,0 +1,89 @@
+use core::cell::Cell;
+
+use fallible_iterator::FallibleIterator;
+
+use super::common::needs_padding;
+use super::streaming::HexDecodeAsciiIterator;
+use super::{DecodeOptions, FromHexError};
+
+/// Decode a hex byte string into the front of the same buffer.
+/// Returns the decoded bytes, which are a prefix of `buf`.
+///
+/// Accepts the same input as [`decode_ascii_to_buf`](crate::decode_ascii_to_buf), including a leading `0x`.
+/// Each decoded byte overwrites input that has already been read, so no second buffer is needed.
+/// On error, the start of `buf` may have been overwritten.
+///
+/// # Example
+///
+/// ```
+/// use hexhex_impl::*;
+/// let mut buf = *b"0xc0ffee";
+/// assert_eq!(decode_in_place(&mut buf).unwrap(), [0xc0, 0xff, 0xee]);
+/// ```
+pub fn decode_in_place(buf: &mut [u8]) -> Result<&mut [u8], FromHexError> {
+    decode_in_place_with_options(buf, DecodeOptions::default())
+}
+
+/// Like [`decode_in_place`], but with the given options.
+pub fn decode_in_place_with_options(
+    buf: &mut [u8],
+    options: DecodeOptions,
+) -> Result<&mut [u8], FromHexError> {
+    let len = decode_in_place_internal(buf, options)?;
+    Ok(&mut buf[..len])
+}
+
+/// Like [`decode_in_place`], but the rest of `buf` (the consumed input) is wiped afterwards,
+/// and all of `buf` on error.
+///
+/// # Example
+///
+/// ```
+/// use hexhex_impl::*;
+/// let mut buf = *b"c0ffee";
+/// assert_eq!(decode_in_place_zeroizing(&mut buf).unwrap(), [0xc0, 0xff, 0xee]);
+/// assert_eq!(buf, [0xc0, 0xff, 0xee, 0, 0, 0]);
+/// ```
+#[cfg(feature = "zeroize")]
+pub fn decode_in_place_zeroizing(buf: &mut [u8]) -> Result<&mut [u8], FromHexError> {
+    decode_in_place_zeroizing_with_options(buf, DecodeOptions::default())
+}
+
+/// Like [`decode_in_place_zeroizing`], but with the given options.
+#[cfg(feature = "zeroize")]
+pub fn decode_in_place_zeroizing_with_options(
+    buf: &mut [u8],
+    options: DecodeOptions,
+) -> Result<&mut [u8], FromHexError> {
+    use zeroize::Zeroize;
+
+    match decode_in_place_internal(buf, options) {
+        Ok(len) => {
+            buf[len..].zeroize();
+            Ok(&mut buf[..len])
+        }
+        Err(err) => {
+            buf.zeroize();
+            Err(err)
+        }
+    }
+}
+
+/// Decode into the front of `buf`, returning the number of bytes
+fn decode_in_place_internal(buf: &mut [u8], options: DecodeOptions) -> Result<usize, FromHexError> {
+    let pad = needs_padding(buf.iter().map(|&b| b as char), &options);
+    // cells allow writing the output while the decoder still reads the input
+    let cells = Cell::from_mut(buf).as_slice_of_cells();
+    let mut iter = HexDecodeAsciiIterator::new_with_options(
+        fallible_iterator::convert(cells.iter().map(Cell::get).enumerate().map(Ok)),
+        options,
+    )
+    .with_padding(pad);
+    let mut len = 0;
+    // byte n is written at index n, which is at most the index of its last digit, so no unread input is overwritten
+    while let Some(byte) = iter.next()? {
+        cells[len].set(byte);
+        len += 1;
+    }
+    Ok(len)
+}
@@ -0,0 +1,134 @@
+use fallible_iterator::FallibleIterator;
+
+use super::common::needs_padding;
+use super::streaming::{HexDecodeAsciiIterator, HexDecodeIterator};
+use super::validate::{validate_ascii_with_options, validate_with_options};
+use super::{DecodeOptions, FromHexError};
+
+/// Adapts one of the streaming iterators to [`Iterator`], with an exact length
+struct DecodeIter<I> {
+    iter: I,
+    /// The number of items left
+    remaining: usize,
+    /// The error found by validating the input, which is the only item
+    error: Option<FromHexError>,
+}
+
+impl<I> DecodeIter<I> {
+    /// `validated` is the result of validating the input, i.e. the number of bytes or the first error
+    fn new(iter: I, validated: Result<usize, FromHexError>) -> Self {
+        match validated {
+            Ok(len) => Self {
+                iter,
+                remaining: len,
+                error: None,
+            },
+            Err(err) => Self {
+                iter,
+                remaining: 1,
+                error: Some(err),
+            },
+        }
+    }
+}
+
+impl<I: FallibleIterator<Item = u8, Error = FromHexError>> Iterator for DecodeIter<I> {
+    type Item = Result<u8, FromHexError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        if let Some(err) = self.error.take() {
+            return Some(Err(err));
+        }
+        // the input is valid, so this yields exactly `remaining` bytes
+        match self.iter.next() {
+            Ok(Some(byte)) => Some(Ok(byte)),
+            Ok(None) => {
+                self.remaining = 0;
+                None
+            }
+            Err(err) => {
+                self.remaining = 0;
+                Some(Err(err))
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<I: FallibleIterator<Item = u8, Error = FromHexError>> ExactSizeIterator for DecodeIter<I> {}
+
+impl<I: FallibleIterator<Item = u8, Error = FromHexError>> core::iter::FusedIterator
+    for DecodeIter<I>
+{
+}
+
+/// Decode a hex string lazily, as an ordinary [`Iterator`] of bytes.
+///
+/// Accepts the same input as [`decode_to_buf`](crate::decode_to_buf), including a leading `0x`.
+/// The input is validated up front (which takes a pass over it), so that the length is exact:
+/// valid input yields only bytes, and invalid input yields only its first error, once.
+///
+/// Does not allocate.
+///
+/// # Example
+///
+/// ```
+/// use hexhex_impl::*;
+/// let iter = decode_iter("0xc0ffee");
+/// assert_eq!(iter.len(), 3);
+/// let bytes: Result<Vec<u8>, _> = iter.collect();
+/// assert_eq!(bytes.unwrap(), [0xc0, 0xff, 0xee]);
+///
+/// let sum: Result<u32, _> = decode_iter("0102").map(|byte| byte.map(u32::from)).sum();
+/// assert_eq!(sum.unwrap(), 3);
+///
+/// let mut iter = decode_iter("c0ffeg");
+/// assert_eq!(iter.len(), 1);
+/// assert_eq!(iter.next().unwrap().unwrap_err().kind(), FromHexErrorKind::UnexpectedCharacter('g'));
+/// assert!(iter.next().is_none());
+/// ```
+pub fn decode_iter(
+    hex: &str,
+) -> impl ExactSizeIterator<Item = Result<u8, FromHexError>> + core::iter::FusedIterator + '_ {
+    decode_iter_with_options(hex, DecodeOptions::default())
+}
+
+/// Like [`decode_iter`], but with the given options.
+pub fn decode_iter_with_options(
+    hex: &str,
+    options: DecodeOptions,
+) -> impl ExactSizeIterator<Item = Result<u8, FromHexError>> + core::iter::FusedIterator + '_ {
+    let iter = HexDecodeIterator::new_with_options(
+        fallible_iterator::convert(hex.char_indices().map(Ok)),
+        options,
+    )
+    .with_padding(needs_padding(hex.chars(), &options));
+    DecodeIter::new(iter, validate_with_options(hex, options))
+}
+
+/// Like [`decode_iter`], but for a byte string.
+pub fn decode_ascii_iter(
+    hex: &[u8],
+) -> impl ExactSizeIterator<Item = Result<u8, FromHexError>> + core::iter::FusedIterator + '_ {
+    decode_ascii_iter_with_options(hex, DecodeOptions::default())
+}
+
+/// Like [`decode_ascii_iter`], but with the given options.
+pub fn decode_ascii_iter_with_options(
+    hex: &[u8],
+    options: DecodeOptions,
+) -> impl ExactSizeIterator<Item = Result<u8, FromHexError>> + core::iter::FusedIterator + '_ {
+    let iter = HexDecodeAsciiIterator::new_with_options(
+        fallible_iterator::convert(hex.iter().copied().enumerate().map(Ok)),
+        options,
+    )
+    .with_padding(needs_padding(hex.iter().map(|&b| b as char), &options));
+    DecodeIter::new(iter, validate_ascii_with_options(hex, options))
+}
@@ -1,23 +1,64 @@
 /// Represents an error encountered while decoding a hex string
 #[derive(Debug, Clone, Copy)]
 pub struct FromHexError {
-    position: usize,
-    kind: FromHexErrorKind,
+    pub(crate) position: usize,
+    pub(crate) kind: FromHexErrorKind,
 }
 
-#[derive(Debug, Clone, Copy)]
+impl FromHexError {
+    /// The position (in bytes) within the input at which the error occurred
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// What went wrong
+    pub fn kind(&self) -> FromHexErrorKind {
+        self.kind
+    }
+}
+
+/// What went wrong while decoding a hex string
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum FromHexErrorKind {
+    /// A character that is not a hex digit (or an allowed separator) in a `&str`.
     UnexpectedCharacter(char),
+    /// A byte that is not a hex digit (or an allowed separator) in a `&[u8]`.
     UnexpectedByte(u8),
-    Eof,
-    OutputBufferTooShort,
+    /// The input ends with an unpaired hex digit; the position is that of the digit.
+    OddLength,
+    /// The input lacks a required `0x` prefix, or has a forbidden one.
+    InvalidPrefix,
+    /// The output buffer is too small; `required` is the number of bytes the whole input decodes to.
+    /// The position is that of the first byte that does not fit.
+    OutputTooSmall { required: usize },
+    /// The output buffer is larger than the decoded input, but must be filled exactly.
+    /// The position is the end of the input.
+    OutputTooLarge,
+    /// A decoded number does not fit into its target type.
+    Overflow,
+    /// Digits are required at the position, but there are none.
+    MissingDigits,
+    /// The input ends within a word; the position is that of its first digit.
+    IncompleteWord,
 }
 
-mod common;
+pub(crate) mod common;
+mod diagnostics;
+mod options;
+
+pub use diagnostics::Snippet;
+pub use options::{CasePolicy, DecodeOptions, OddLengthPolicy, PrefixPolicy};
 
 //pub mod generic;
+pub mod array;
 pub mod buf;
+pub mod in_place;
+pub mod iter;
+pub mod sink;
 pub mod streaming;
+pub mod validate;
+pub mod words;
 
 #[cfg(feature = "std")]
 pub mod vec;
@@ -0,0 +1,109 @@
+/// Whether a leading `0x` prefix is accepted when decoding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum PrefixPolicy {
+    /// Strip a leading `0x` if present.
+    #[default]
+    Optional,
+    /// Reject input that does not start with `0x`.
+    Required,
+    /// Reject input that starts with `0x`.
+    Forbidden,
+}
+
+/// Which letter case is accepted for the hex digits a-f when decoding
+///
+/// The `0x` prefix is always lower case and not affected by this policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum CasePolicy {
+    /// Accept lower case, upper case, and mixed case.
+    #[default]
+    Any,
+    /// Accept only lower case (a-f).
+    Lower,
+    /// Accept only upper case (A-F).
+    Upper,
+    /// Accept either lower or upper case, but not both in the same input.
+    Consistent,
+}
+
+/// How input with an odd number of hex digits is handled when decoding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum OddLengthPolicy {
+    /// Reject input with an odd number of hex digits.
+    #[default]
+    Reject,
+    /// Treat input with an odd number of hex digits as if it had an additional leading `0`,
+    /// e.g. `0xfff` decodes to `[0x0f, 0xff]`.
+    ///
+    /// This requires knowing the length of the input in advance, so the streaming iterators
+    /// ([`HexDecodeIterator`](crate::HexDecodeIterator) and [`HexDecodeAsciiIterator`](crate::HexDecodeAsciiIterator))
+    /// reject odd-length input regardless.
+    PadLeft,
+}
+
+/// Options for decoding hex to bytes
+///
+/// The default options are what `decode` and friends use:
+/// an optional `0x` prefix, any case, no separators, and an even number of digits.
+///
+/// # Example
+///
+/// Only accept the canonical encoding as produced by [`Hex`](crate::Hex) with default options:
+///
+/// ```
+/// use hexhex_impl::*;
+/// let options = DecodeOptions::new()
+///     .with_prefix(PrefixPolicy::Forbidden)
+///     .with_case(CasePolicy::Lower);
+/// let mut buf = [0u8; 3];
+/// assert!(decode_to_buf_exact_with_options("c0ffee", &mut buf, options).is_ok());
+/// assert!(decode_to_buf_exact_with_options("0xc0ffee", &mut buf, options).is_err());
+/// assert!(decode_to_buf_exact_with_options("C0FFEE", &mut buf, options).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct DecodeOptions {
+    /// Whether a leading `0x` is accepted, required, or rejected.
+    pub prefix: PrefixPolicy,
+    /// Which letter case is accepted for the digits a-f.
+    pub case: CasePolicy,
+    /// ASCII characters that may appear before, between, and after bytes (but not within a byte).
+    /// Non-ASCII characters in this string are ignored.
+    pub separators: &'static str,
+    /// Whether an odd number of hex digits is rejected or padded.
+    pub odd_length: OddLengthPolicy,
+}
+
+impl DecodeOptions {
+    /// Create new default decode options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use the given prefix policy
+    pub fn with_prefix(mut self, prefix: PrefixPolicy) -> Self {
+        self.prefix = prefix;
+        self
+    }
+
+    /// Use the given case policy
+    pub fn with_case(mut self, case: CasePolicy) -> Self {
+        self.case = case;
+        self
+    }
+
+    /// Allow the given separator characters between bytes
+    pub fn with_separators(mut self, separators: &'static str) -> Self {
+        self.separators = separators;
+        self
+    }
+
+    /// Use the given odd length policy
+    pub fn with_odd_length(mut self, odd_length: OddLengthPolicy) -> Self {
+        self.odd_length = odd_length;
+        self
+    }
+
+    pub(crate) fn is_separator(&self, c: u8) -> bool {
+        c.is_ascii() && self.separators.as_bytes().contains(&c)
+    }
+}
@@ -0,0 +1,170 @@
+use fallible_iterator::FallibleIterator;
+
+use super::common::needs_padding;
+use super::streaming::{HexDecodeAsciiIterator, HexDecodeIterator};
+use super::validate::{validate_ascii_with_options, validate_with_options};
+use super::{DecodeOptions, FromHexError, FromHexErrorKind};
+
+/// Destination that decoded bytes are appended to
+///
+/// Implemented for `Vec<u8>` (with the `std` feature), and behind features of the same name for
+/// `bytes::BytesMut` and any `bytes::BufMut` (via `BufMutSink`), `heapless::Vec<u8, N>`,
+/// and `arrayvec::ArrayVec<u8, N>`.
+pub trait DecodeSink {
+    /// Make room for `len` more bytes, or return `false` if they do not fit.
+    fn try_reserve(&mut self, len: usize) -> bool;
+
+    /// Append a byte. Only called for bytes that room has been made for.
+    fn push(&mut self, byte: u8);
+}
+
+#[cfg(feature = "std")]
+impl DecodeSink for std::vec::Vec<u8> {
+    fn try_reserve(&mut self, len: usize) -> bool {
+        std::vec::Vec::try_reserve(self, len).is_ok()
+    }
+
+    fn push(&mut self, byte: u8) {
+        std::vec::Vec::push(self, byte);
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl DecodeSink for bytes::BytesMut {
+    fn try_reserve(&mut self, len: usize) -> bool {
+        self.reserve(len);
+        true
+    }
+
+    fn push(&mut self, byte: u8) {
+        bytes::BufMut::put_u8(self, byte);
+    }
+}
+
+/// Adapter to decode into any [`bytes::BufMut`], as far as its remaining capacity allows
+///
+/// # Example
+///
+/// ```
+/// use hexhex_impl::*;
+/// let mut buf = [0u8; 4];
+/// let mut sink = BufMutSink(&mut buf[..]);
+/// assert_eq!(decode_into("c0ffee", &mut sink).unwrap(), 3);
+/// assert_eq!(buf, [0xc0, 0xff, 0xee, 0x00]);
+/// ```
+#[cfg(feature = "bytes")]
+#[derive(Debug)]
+pub struct BufMutSink<B>(pub B);
+
+#[cfg(feature = "bytes")]
+impl<B: bytes::BufMut> DecodeSink for BufMutSink<B> {
+    fn try_reserve(&mut self, len: usize) -> bool {
+        self.0.remaining_mut() >= len
+    }
+
+    fn push(&mut self, byte: u8) {
+        self.0.put_u8(byte);
+    }
+}
+
+#[cfg(feature = "heapless")]
+impl<const N: usize> DecodeSink for heapless::Vec<u8, N> {
+    fn try_reserve(&mut self, len: usize) -> bool {
+        self.capacity() - self.len() >= len
+    }
+
+    fn push(&mut self, byte: u8) {
+        // cannot fail, room has been made
+        let _ = heapless::Vec::push(self, byte);
+    }
+}
+
+#[cfg(feature = "arrayvec")]
+impl<const N: usize> DecodeSink for arrayvec::ArrayVec<u8, N> {
+    fn try_reserve(&mut self, len: usize) -> bool {
+        self.remaining_capacity() >= len
+    }
+
+    fn push(&mut self, byte: u8) {
+        // cannot fail, room has been made
+        let _ = self.try_push(byte);
+    }
+}
+
+fn decode_into_internal<S, I>(sink: &mut S, len: usize, mut iter: I) -> Result<usize, FromHexError>
+where
+    S: DecodeSink + ?Sized,
+    I: FallibleIterator<Item = u8, Error = FromHexError>,
+{
+    if !sink.try_reserve(len) {
+        return Err(FromHexError {
+            position: 0,
+            kind: FromHexErrorKind::OutputTooSmall { required: len },
+        });
+    }
+    while let Some(byte) = iter.next()? {
+        sink.push(byte);
+    }
+    Ok(len)
+}
+
+/// Decode a hex string, appending the bytes to `sink`.
+/// Returns the number of bytes appended.
+///
+/// Accepts the same input as [`decode_to_buf`](crate::decode_to_buf).
+/// The input is validated first, so the sink is left untouched on error.
+/// If the bytes do not fit, the error is [`FromHexErrorKind::OutputTooSmall`] at position 0.
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(feature = "std")]
+/// # {
+/// use hexhex_impl::*;
+/// let mut bytes = vec![0x01];
+/// assert_eq!(decode_into("0xc0ffee", &mut bytes).unwrap(), 3);
+/// assert!(decode_into("c0ffeg", &mut bytes).is_err());
+/// assert_eq!(bytes, [0x01, 0xc0, 0xff, 0xee]);
+/// # }
+/// ```
+pub fn decode_into<S: DecodeSink + ?Sized>(hex: &str, sink: &mut S) -> Result<usize, FromHexError> {
+    decode_into_with_options(hex, sink, DecodeOptions::default())
+}
+
+/// Like [`decode_into`], but with the given options.
+pub fn decode_into_with_options<S: DecodeSink + ?Sized>(
+    hex: &str,
+    sink: &mut S,
+    options: DecodeOptions,
+) -> Result<usize, FromHexError> {
+    let len = validate_with_options(hex, options)?;
+    let iter = HexDecodeIterator::new_with_options(
+        fallible_iterator::convert(hex.char_indices().map(Ok)),
+        options,
+    )
+    .with_padding(needs_padding(hex.chars(), &options));
+    decode_into_internal(sink, len, iter)
+}
+
+/// Like [`decode_into`], but for a byte string.
+pub fn decode_ascii_into<S: DecodeSink + ?Sized>(
+    hex: &[u8],
+    sink: &mut S,
+) -> Result<usize, FromHexError> {
+    decode_ascii_into_with_options(hex, sink, DecodeOptions::default())
+}
+
+/// Like [`decode_ascii_into`], but with the given options.
+pub fn decode_ascii_into_with_options<S: DecodeSink + ?Sized>(
+    hex: &[u8],
+    sink: &mut S,
+    options: DecodeOptions,
+) -> Result<usize, FromHexError> {
+    let len = validate_ascii_with_options(hex, options)?;
+    let iter = HexDecodeAsciiIterator::new_with_options(
+        fallible_iterator::convert(hex.iter().copied().enumerate().map(Ok)),
+        options,
+    )
+    .with_padding(needs_padding(hex.iter().map(|&b| b as char), &options));
+    decode_into_internal(sink, len, iter)
+}
@@ -1,6 +1,7 @@
 use fallible_iterator::FallibleIterator;
 
-use super::{FromHexError, FromHexErrorKind};
+use super::common::Decoder;
+use super::{DecodeOptions, FromHexError, FromHexErrorKind, PrefixPolicy};
 
 /// Fallible iterator that produces u8 from a hex string (or byte string) one at a time.
 /// Accepts lowercase, uppercase, and mixedcase hex digits a-f.
@@ -9,11 +10,16 @@ use super::{FromHexError, FromHexErrorKind};
 /// The intended use is to take in either:
 /// - a `str.char_indices()` iterator, or
 /// - an `byte_slice.iter().copied().enumerate()` iterator
+///
 /// and decode it one byte at a time via the `FallibleIterator` interface.
 ///
-/// You should probably not use this.
+/// Iteration may continue after an error, as if each invalid character were a valid digit.
+/// This is how [`validate_all`](crate::validate_all) finds all errors.
+///
+/// You should probably not use this, [`decode_iter`](crate::decode_iter) is an ordinary [`Iterator`].
 pub struct HexDecodeIterator<I> {
     iterator: I,
+    decoder: Decoder,
 }
 
 /// Like HexDecodeIterator, but for (ASCII) `u8` instead of `char`s.
@@ -23,7 +29,12 @@ pub struct HexDecodeIterator<I> {
 /// You should probably not use this.
 pub struct HexDecodeAsciiIterator<I> {
     iterator: I,
-    last_position: usize,
+    decoder: Decoder,
+}
+
+/// Options used by the iterators' `new` constructors: like the defaults, but without a prefix.
+fn no_prefix() -> DecodeOptions {
+    DecodeOptions::default().with_prefix(PrefixPolicy::Forbidden)
 }
 
 impl<I: FallibleIterator<Item = (usize, char), Error = FromHexError>> HexDecodeIterator<I> {
@@ -31,7 +42,40 @@ impl<I: FallibleIterator<Item = (usize, char), Error = FromHexError>> HexDecodeI
     ///
     /// The usize should be the (byte) position of the corresponding char.
     pub fn new(iterator: I) -> Self {
-        Self { iterator }
+        Self::new_with_options(iterator, no_prefix())
+    }
+
+    /// Construct a new HexDecodeIterator from a `(usize, char)` iterator with the given options.
+    ///
+    /// Unlike with [`HexDecodeIterator::new`], a leading `0x` is handled according to `options.prefix`.
+    /// Separators must be ASCII.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fallible_iterator::FallibleIterator;
+    /// use hexhex_impl::*;
+    /// let options = DecodeOptions::new().with_separators(":");
+    /// let chars = fallible_iterator::convert("0xc0:ff:ee".char_indices().map(Ok));
+    /// let iter = HexDecodeIterator::new_with_options(chars, options);
+    /// let bytes: Vec<u8> = iter.iterator().collect::<Result<_, _>>().unwrap();
+    /// assert_eq!(bytes, [0xc0, 0xff, 0xee]);
+    /// ```
+    pub fn new_with_options(iterator: I, options: DecodeOptions) -> Self {
+        Self {
+            iterator,
+            decoder: Decoder::new(options),
+        }
+    }
+
+    /// Decode the first digit as a whole byte, as if preceded by a `0`.
+    pub(crate) fn with_padding(mut self, pad: bool) -> Self {
+        self.decoder.set_pad(pad);
+        self
+    }
+
+    pub(crate) fn byte_position(&self) -> usize {
+        self.decoder.byte_position()
     }
 }
 
@@ -40,14 +84,94 @@ impl<I: FallibleIterator<Item = (usize, u8), Error = FromHexError>> HexDecodeAsc
     ///
     /// The usize should be the (byte) position of the corresponding ASCII u8.
     pub fn new(iterator: I) -> Self {
+        Self::new_with_options(iterator, no_prefix())
+    }
+
+    /// Construct a new HexDecodeAsciiIterator from a `(usize, byte)` iterator with the given options.
+    ///
+    /// Unlike with [`HexDecodeAsciiIterator::new`], a leading `0x` is handled according to `options.prefix`.
+    pub fn new_with_options(iterator: I, options: DecodeOptions) -> Self {
         Self {
             iterator,
-            last_position: 0,
+            decoder: Decoder::new(options),
         }
     }
 
-    pub(crate) fn last_position(&self) -> usize {
-        self.last_position
+    /// Decode the first digit as a whole byte, as if preceded by a `0`.
+    pub(crate) fn with_padding(mut self, pad: bool) -> Self {
+        self.decoder.set_pad(pad);
+        self
+    }
+
+    pub(crate) fn byte_position(&self) -> usize {
+        self.decoder.byte_position()
+    }
+}
+
+/// Input for [`HexDecodeAsciiIterator`] that wipes each byte of a buffer once it has been read
+///
+/// # Example
+///
+/// ```
+/// use hexhex_impl::*;
+/// use fallible_iterator::FallibleIterator;
+/// let mut input = *b"c0ffee";
+/// let mut iter = HexDecodeAsciiIterator::new(ZeroizingInput::new(&mut input));
+/// assert_eq!(iter.next().unwrap(), Some(0xc0));
+/// drop(iter);
+/// assert_eq!(input, [0, 0, b'f', b'f', b'e', b'e']);
+/// ```
+#[cfg(feature = "zeroize")]
+#[derive(Debug)]
+pub struct ZeroizingInput<'a> {
+    buf: &'a mut [u8],
+    position: usize,
+}
+
+#[cfg(feature = "zeroize")]
+impl<'a> ZeroizingInput<'a> {
+    /// Read the bytes of `buf`, wiping each one as it is read.
+    pub fn new(buf: &'a mut [u8]) -> Self {
+        Self { buf, position: 0 }
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl FallibleIterator for ZeroizingInput<'_> {
+    type Item = (usize, u8);
+    type Error = FromHexError;
+
+    fn next(&mut self) -> Result<Option<(usize, u8)>, FromHexError> {
+        let position = self.position;
+        let Some(byte) = self.buf.get_mut(position) else {
+            return Ok(None);
+        };
+        let value = *byte;
+        zeroize::Zeroize::zeroize(byte);
+        self.position += 1;
+        Ok(Some((position, value)))
+    }
+}
+
+/// Adapter that turns a char iterator into an ASCII byte iterator, rejecting non-ASCII chars
+struct AsciiChars<'a, I>(&'a mut I);
+
+impl<I> FallibleIterator for AsciiChars<'_, I>
+where
+    I: FallibleIterator<Item = (usize, char), Error = FromHexError>,
+{
+    type Item = (usize, u8);
+    type Error = FromHexError;
+
+    fn next(&mut self) -> Result<Option<(usize, u8)>, FromHexError> {
+        match self.0.next()? {
+            None => Ok(None),
+            Some((position, c)) if c.is_ascii() => Ok(Some((position, c as u8))),
+            Some((position, c)) => Err(FromHexError {
+                position,
+                kind: FromHexErrorKind::UnexpectedCharacter(c),
+            }),
+        }
     }
 }
 
@@ -59,37 +183,16 @@ where
     type Error = FromHexError;
 
     fn next(&mut self) -> Result<Option<u8>, FromHexError> {
-        // read first char
-        let (position, c) = match self.iterator.next() {
-            Ok(Some(tup)) => tup,
-            Ok(None) => return Ok(None),
-            Err(e) => return Err(e),
-        };
-        let Some(v1) = c.to_digit(16) else {
-            return Err(FromHexError {
-                position,
-                kind: FromHexErrorKind::UnexpectedCharacter(c),
-            });
-        };
-        // read second char
-        let (position, c) = match self.iterator.next() {
-            Ok(Some(tup)) => tup,
-            Ok(None) => {
-                return Err(FromHexError {
-                    position,
-                    kind: FromHexErrorKind::Eof,
-                })
-            }
-            Err(e) => return Err(e),
-        };
-        let Some(v2) = c.to_digit(16) else {
-            return Err(FromHexError {
-                position,
-                kind: FromHexErrorKind::UnexpectedCharacter(c),
-            });
-        };
-        // Got two hex digits, done
-        Ok(Some((v1 * 16 + v2) as u8))
+        self.decoder
+            .next(&mut AsciiChars(&mut self.iterator))
+            .map_err(|err| match err.kind {
+                // the decoder only sees ASCII bytes constructed from chars, so this cast is correct
+                FromHexErrorKind::UnexpectedByte(byte) => FromHexError {
+                    position: err.position,
+                    kind: FromHexErrorKind::UnexpectedCharacter(byte as char),
+                },
+                _ => err,
+            })
     }
 }
 
@@ -101,37 +204,6 @@ where
     type Error = FromHexError;
 
     fn next(&mut self) -> Result<Option<u8>, FromHexError> {
-        // read first char
-        let (position, c) = match self.iterator.next() {
-            Ok(Some(tup)) => tup,
-            Ok(None) => return Ok(None),
-            Err(e) => return Err(e),
-        };
-        let Some(v1) = (c as char).to_digit(16) else {
-            return Err(FromHexError {
-                position,
-                kind: FromHexErrorKind::UnexpectedByte(c),
-            });
-        };
-        // read second char
-        let (position, c) = match self.iterator.next() {
-            Ok(Some(tup)) => tup,
-            Ok(None) => {
-                return Err(FromHexError {
-                    position,
-                    kind: FromHexErrorKind::Eof,
-                })
-            }
-            Err(e) => return Err(e),
-        };
-        let Some(v2) = (c as char).to_digit(16) else {
-            return Err(FromHexError {
-                position,
-                kind: FromHexErrorKind::UnexpectedByte(c),
-            });
-        };
-        self.last_position = position;
-        // Got two hex digits, done
-        Ok(Some((v1 * 16 + v2) as u8))
+        self.decoder.next(&mut self.iterator)
     }
 }
@@ -0,0 +1,145 @@
+use fallible_iterator::FallibleIterator;
+
+use super::common::needs_padding;
+use super::streaming::{HexDecodeAsciiIterator, HexDecodeIterator};
+use super::{DecodeOptions, FromHexError};
+
+/// Check whether a hex string is valid without decoding it anywhere.
+/// Returns the number of bytes it decodes to.
+///
+/// Accepts the same input as [`decode_to_buf`](crate::decode_to_buf), including a leading `0x`.
+///
+/// Does not allocate.
+///
+/// # Example
+///
+/// ```
+/// use hexhex_impl::*;
+/// assert_eq!(validate("0xc0ffee").unwrap(), 3);
+/// assert_eq!(validate("c0ffe").unwrap_err().kind(), FromHexErrorKind::OddLength);
+/// ```
+pub fn validate(hex: &str) -> Result<usize, FromHexError> {
+    validate_with_options(hex, DecodeOptions::default())
+}
+
+/// Like [`validate`], but with the given options.
+pub fn validate_with_options(hex: &str, options: DecodeOptions) -> Result<usize, FromHexError> {
+    HexDecodeIterator::new_with_options(
+        fallible_iterator::convert(hex.char_indices().map(Ok)),
+        options,
+    )
+    .with_padding(needs_padding(hex.chars(), &options))
+    .count()
+}
+
+/// Like [`validate`], but for a byte string.
+///
+/// # Example
+///
+/// ```
+/// use hexhex_impl::*;
+/// assert_eq!(validate_ascii(b"c0ffee").unwrap(), 3);
+/// assert_eq!(validate_ascii(b"c0\xff").unwrap_err().kind(), FromHexErrorKind::UnexpectedByte(0xff));
+/// ```
+pub fn validate_ascii(hex: &[u8]) -> Result<usize, FromHexError> {
+    validate_ascii_with_options(hex, DecodeOptions::default())
+}
+
+/// Like [`validate_ascii`], but with the given options.
+pub fn validate_ascii_with_options(
+    hex: &[u8],
+    options: DecodeOptions,
+) -> Result<usize, FromHexError> {
+    HexDecodeAsciiIterator::new_with_options(
+        fallible_iterator::convert(hex.iter().copied().enumerate().map(Ok)),
+        options,
+    )
+    .with_padding(needs_padding(hex.iter().map(|&b| b as char), &options))
+    .count()
+}
+
+/// The largest number of bytes that a hex string of `len` bytes can decode to, for sizing buffers.
+///
+/// This is exact for hex strings without prefix or separators (rounding up for an odd length),
+/// and can be used in const contexts. Use [`validate`] for the exact number of bytes.
+///
+/// # Example
+///
+/// ```
+/// use hexhex_impl::*;
+/// const KEY_HEX: &str = "00112233445566778899aabbccddeeff";
+/// let mut key = [0u8; decoded_len(KEY_HEX.len())];
+/// decode_to_buf_exact(KEY_HEX, &mut key).unwrap();
+/// ```
+pub const fn decoded_len(len: usize) -> usize {
+    len.div_ceil(2)
+}
+
+/// Find all errors in a hex string, instead of stopping at the first one.
+///
+/// Accepts the same input as [`decode_to_buf`](crate::decode_to_buf), and reports the same positions.
+/// After an error, validation continues as if each invalid character were a valid digit,
+/// so a final odd length is only reported if the number of characters is odd.
+///
+/// Does not allocate.
+///
+/// # Example
+///
+/// ```
+/// use hexhex_impl::*;
+/// let errors: Vec<_> = validate_all("0xc0fg3e!").map(|e| (e.position(), e.kind())).collect();
+/// assert_eq!(
+///     errors,
+///     [
+///         (5, FromHexErrorKind::UnexpectedCharacter('g')),
+///         (8, FromHexErrorKind::UnexpectedCharacter('!')),
+///         (8, FromHexErrorKind::OddLength),
+///     ]
+/// );
+/// ```
+pub fn validate_all(hex: &str) -> impl Iterator<Item = FromHexError> + '_ {
+    validate_all_with_options(hex, DecodeOptions::default())
+}
+
+/// Like [`validate_all`], but with the given options.
+pub fn validate_all_with_options(
+    hex: &str,
+    options: DecodeOptions,
+) -> impl Iterator<Item = FromHexError> + '_ {
+    let mut iter = HexDecodeIterator::new_with_options(
+        fallible_iterator::convert(hex.char_indices().map(Ok)),
+        options,
+    )
+    .with_padding(needs_padding(hex.chars(), &options));
+    core::iter::from_fn(move || loop {
+        match iter.next() {
+            Ok(Some(_)) => {}
+            Ok(None) => return None,
+            Err(err) => return Some(err),
+        }
+    })
+}
+
+/// Like [`validate_all_with_options`], but calls `f` for each error.
+/// Returns the number of errors.
+///
+/// # Example
+///
+/// ```
+/// use hexhex_impl::*;
+/// let mut positions = [0; 4];
+/// let count = validate_all_with_callback("x0y0", DecodeOptions::new(), |e| {
+///     positions[e.position()] += 1;
+/// });
+/// assert_eq!(count, 2);
+/// assert_eq!(positions, [1, 0, 1, 0]);
+/// ```
+pub fn validate_all_with_callback(
+    hex: &str,
+    options: DecodeOptions,
+    mut f: impl FnMut(FromHexError),
+) -> usize {
+    validate_all_with_options(hex, options)
+        .inspect(|&err| f(err))
+        .count()
+}
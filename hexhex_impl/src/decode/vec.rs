@@ -1,22 +1,43 @@
-use crate::decode::buf::{decode_ascii_to_buf_exact, decode_to_buf_exact};
-use crate::decode::common::{has_0x_prefix, has_0x_prefix_ascii};
-use crate::decode::FromHexError;
+use fallible_iterator::FallibleIterator;
 
-use std::vec;
+use crate::decode::common::needs_padding;
+use crate::decode::streaming::{HexDecodeAsciiIterator, HexDecodeIterator};
+use crate::decode::{DecodeOptions, FromHexError};
+
+use std::vec::Vec;
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroizing;
 
 /// Decode a hex string.
 ///
 /// Accepts lowercase, uppercase, and mixedcase hex digits a-f.
 /// Strips leading `0x` prefix if present.
 pub fn decode(hex: &str) -> Result<std::vec::Vec<u8>, FromHexError> {
-    let expected_len = if has_0x_prefix(hex) {
-        (hex.len() - 2) / 2
-    } else {
-        hex.len() / 2
-    };
-    let mut vec = vec![0u8; expected_len];
-    decode_to_buf_exact(hex, &mut vec)?;
-    Ok(vec)
+    decode_with_options(hex, DecodeOptions::default())
+}
+
+/// Like [`decode`], but with the given options.
+///
+/// # Example
+///
+/// ```
+/// use hexhex_impl::*;
+/// let options = DecodeOptions::new().with_separators(" ");
+/// assert_eq!(decode_with_options("c0 ff ee", options).unwrap(), [0xc0, 0xff, 0xee]);
+///
+/// let options = DecodeOptions::new().with_odd_length(OddLengthPolicy::PadLeft);
+/// assert_eq!(decode_with_options("0xfff", options).unwrap(), [0x0f, 0xff]);
+/// ```
+pub fn decode_with_options(
+    hex: &str,
+    options: DecodeOptions,
+) -> Result<std::vec::Vec<u8>, FromHexError> {
+    HexDecodeIterator::new_with_options(
+        fallible_iterator::convert(hex.char_indices().map(Ok)),
+        options,
+    )
+    .with_padding(needs_padding(hex.chars(), &options))
+    .fold(Vec::with_capacity(hex.len() / 2 + 1), push)
 }
 
 /// Decode a hex bytestring.
@@ -24,12 +45,91 @@ pub fn decode(hex: &str) -> Result<std::vec::Vec<u8>, FromHexError> {
 /// Accepts lowercase, uppercase, and mixedcase hex digits a-f.
 /// Strips leading `0x` prefix if present.
 pub fn decode_ascii(hex: &[u8]) -> Result<std::vec::Vec<u8>, FromHexError> {
-    let expected_len = if has_0x_prefix_ascii(hex) {
-        (hex.len() - 2) / 2
-    } else {
-        hex.len() / 2
-    };
-    let mut vec = vec![0u8; expected_len];
-    decode_ascii_to_buf_exact(hex, &mut vec)?;
+    decode_ascii_with_options(hex, DecodeOptions::default())
+}
+
+/// Like [`decode_ascii`], but with the given options.
+///
+/// # Example
+///
+/// ```
+/// use hexhex_impl::*;
+/// let options = DecodeOptions::new().with_case(CasePolicy::Upper);
+/// assert_eq!(decode_ascii_with_options(b"C0FFEE", options).unwrap(), [0xc0, 0xff, 0xee]);
+/// assert!(decode_ascii_with_options(b"c0ffee", options).is_err());
+/// ```
+pub fn decode_ascii_with_options(
+    hex: &[u8],
+    options: DecodeOptions,
+) -> Result<std::vec::Vec<u8>, FromHexError> {
+    HexDecodeAsciiIterator::new_with_options(
+        fallible_iterator::convert(hex.iter().copied().enumerate().map(Ok)),
+        options,
+    )
+    .with_padding(needs_padding(hex.iter().map(|&b| b as char), &options))
+    .fold(Vec::with_capacity(hex.len() / 2 + 1), push)
+}
+
+/// Like [`decode`], but the bytes are wiped from memory when dropped, and on error.
+///
+/// # Example
+///
+/// ```
+/// use hexhex_impl::*;
+/// let key = decode_zeroizing("0xc0ffee").unwrap();
+/// assert_eq!(*key, [0xc0, 0xff, 0xee]);
+/// ```
+#[cfg(feature = "zeroize")]
+pub fn decode_zeroizing(hex: &str) -> Result<Zeroizing<Vec<u8>>, FromHexError> {
+    decode_zeroizing_with_options(hex, DecodeOptions::default())
+}
+
+/// Like [`decode_zeroizing`], but with the given options.
+#[cfg(feature = "zeroize")]
+pub fn decode_zeroizing_with_options(
+    hex: &str,
+    options: DecodeOptions,
+) -> Result<Zeroizing<Vec<u8>>, FromHexError> {
+    // allocated once with enough room, so that growing leaves no copies behind
+    let mut bytes = Zeroizing::new(Vec::with_capacity(hex.len() / 2 + 1));
+    HexDecodeIterator::new_with_options(
+        fallible_iterator::convert(hex.char_indices().map(Ok)),
+        options,
+    )
+    .with_padding(needs_padding(hex.chars(), &options))
+    .for_each(|byte| {
+        bytes.push(byte);
+        Ok(())
+    })?;
+    Ok(bytes)
+}
+
+/// Like [`decode_ascii`], but the bytes are wiped from memory when dropped, and on error.
+#[cfg(feature = "zeroize")]
+pub fn decode_ascii_zeroizing(hex: &[u8]) -> Result<Zeroizing<Vec<u8>>, FromHexError> {
+    decode_ascii_zeroizing_with_options(hex, DecodeOptions::default())
+}
+
+/// Like [`decode_ascii_zeroizing`], but with the given options.
+#[cfg(feature = "zeroize")]
+pub fn decode_ascii_zeroizing_with_options(
+    hex: &[u8],
+    options: DecodeOptions,
+) -> Result<Zeroizing<Vec<u8>>, FromHexError> {
+    let mut bytes = Zeroizing::new(Vec::with_capacity(hex.len() / 2 + 1));
+    HexDecodeAsciiIterator::new_with_options(
+        fallible_iterator::convert(hex.iter().copied().enumerate().map(Ok)),
+        options,
+    )
+    .with_padding(needs_padding(hex.iter().map(|&b| b as char), &options))
+    .for_each(|byte| {
+        bytes.push(byte);
+        Ok(())
+    })?;
+    Ok(bytes)
+}
+
+fn push(mut vec: Vec<u8>, byte: u8) -> Result<Vec<u8>, FromHexError> {
+    vec.push(byte);
     Ok(vec)
 }
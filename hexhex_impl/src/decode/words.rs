@@ -0,0 +1,200 @@
+use fallible_iterator::FallibleIterator;
+
+use super::common::needs_padding;
+use super::streaming::{HexDecodeAsciiIterator, HexDecodeIterator};
+use super::{DecodeOptions, FromHexError, FromHexErrorKind};
+use crate::endian::{Endian, Word};
+
+/// Decode bytes from `iter` into words, passing each to `push`, which returns `false` if there is no room.
+/// Returns the number of words.
+fn decode_words_internal<W, I>(
+    mut iter: I,
+    position: impl Fn(&I) -> usize,
+    endian: Endian,
+    mut push: impl FnMut(W) -> bool,
+) -> Result<usize, FromHexError>
+where
+    W: Word,
+    I: FallibleIterator<Item = u8, Error = FromHexError>,
+{
+    let mut bytes = [0u8; 16];
+    let mut filled = 0;
+    let mut count = 0;
+    let mut word_position = 0;
+    while let Some(byte) = iter.next()? {
+        if filled == 0 {
+            word_position = position(&iter);
+        }
+        bytes[filled] = byte;
+        filled += 1;
+        if filled < W::SIZE {
+            continue;
+        }
+        filled = 0;
+        if !push(W::from_bytes(&bytes[..W::SIZE], endian)) {
+            // the rest of the input must still be valid to tell how much space it needs
+            let required = count + 1 + iter.count()?.div_ceil(W::SIZE);
+            return Err(FromHexError {
+                position: word_position,
+                kind: FromHexErrorKind::OutputTooSmall { required },
+            });
+        }
+        count += 1;
+    }
+    if filled != 0 {
+        return Err(FromHexError {
+            position: word_position,
+            kind: FromHexErrorKind::IncompleteWord,
+        });
+    }
+    Ok(count)
+}
+
+/// Decode a hex string into words of the given byte order, and write them to `dst`.
+/// Returns the number of words written.
+///
+/// Accepts the same input as [`decode_to_buf`](crate::decode_to_buf), which must decode to whole words;
+/// otherwise, the error is [`FromHexErrorKind::IncompleteWord`] at the first digit of the incomplete word.
+/// Like there, `dst` may be larger than needed, and [`FromHexErrorKind::OutputTooSmall`] counts words.
+///
+/// # Example
+///
+/// ```
+/// use hexhex_impl::*;
+/// let mut words = [0u32; 2];
+/// assert_eq!(decode_words("deadbeef00000001", Endian::Big, &mut words).unwrap(), 2);
+/// assert_eq!(words, [0xdeadbeef, 1]);
+///
+/// let err = decode_words("deadbeef0000", Endian::Little, &mut words).unwrap_err();
+/// assert_eq!((err.position(), err.kind()), (8, FromHexErrorKind::IncompleteWord));
+/// ```
+pub fn decode_words<W: Word>(
+    hex: &str,
+    endian: Endian,
+    dst: &mut [W],
+) -> Result<usize, FromHexError> {
+    decode_words_with_options(hex, endian, dst, DecodeOptions::default())
+}
+
+/// Like [`decode_words`], but with the given options.
+pub fn decode_words_with_options<W: Word>(
+    hex: &str,
+    endian: Endian,
+    dst: &mut [W],
+    options: DecodeOptions,
+) -> Result<usize, FromHexError> {
+    let mut slots = dst.iter_mut();
+    decode_words_internal(
+        HexDecodeIterator::new_with_options(
+            fallible_iterator::convert(hex.char_indices().map(Ok)),
+            options,
+        )
+        .with_padding(needs_padding(hex.chars(), &options)),
+        HexDecodeIterator::byte_position,
+        endian,
+        |word| slots.next().map(|slot| *slot = word).is_some(),
+    )
+}
+
+/// Like [`decode_words`], but for a byte string.
+pub fn decode_ascii_words<W: Word>(
+    hex: &[u8],
+    endian: Endian,
+    dst: &mut [W],
+) -> Result<usize, FromHexError> {
+    decode_ascii_words_with_options(hex, endian, dst, DecodeOptions::default())
+}
+
+/// Like [`decode_ascii_words`], but with the given options.
+pub fn decode_ascii_words_with_options<W: Word>(
+    hex: &[u8],
+    endian: Endian,
+    dst: &mut [W],
+    options: DecodeOptions,
+) -> Result<usize, FromHexError> {
+    let mut slots = dst.iter_mut();
+    decode_words_internal(
+        HexDecodeAsciiIterator::new_with_options(
+            fallible_iterator::convert(hex.iter().copied().enumerate().map(Ok)),
+            options,
+        )
+        .with_padding(needs_padding(hex.iter().map(|&b| b as char), &options)),
+        HexDecodeAsciiIterator::byte_position,
+        endian,
+        |word| slots.next().map(|slot| *slot = word).is_some(),
+    )
+}
+
+/// Like [`decode_words`], but returns a new vector of words.
+///
+/// # Example
+///
+/// ```
+/// use hexhex_impl::*;
+/// let words: Vec<u16> = decode_words_vec("0x34127856", Endian::Little).unwrap();
+/// assert_eq!(words, [0x1234, 0x5678]);
+/// ```
+#[cfg(feature = "std")]
+pub fn decode_words_vec<W: Word>(
+    hex: &str,
+    endian: Endian,
+) -> Result<std::vec::Vec<W>, FromHexError> {
+    decode_words_vec_with_options(hex, endian, DecodeOptions::default())
+}
+
+/// Like [`decode_words_vec`], but with the given options.
+#[cfg(feature = "std")]
+pub fn decode_words_vec_with_options<W: Word>(
+    hex: &str,
+    endian: Endian,
+    options: DecodeOptions,
+) -> Result<std::vec::Vec<W>, FromHexError> {
+    let mut words = std::vec::Vec::with_capacity(hex.len() / (2 * W::SIZE));
+    decode_words_internal(
+        HexDecodeIterator::new_with_options(
+            fallible_iterator::convert(hex.char_indices().map(Ok)),
+            options,
+        )
+        .with_padding(needs_padding(hex.chars(), &options)),
+        HexDecodeIterator::byte_position,
+        endian,
+        |word| {
+            words.push(word);
+            true
+        },
+    )?;
+    Ok(words)
+}
+
+/// Like [`decode_words_vec`], but for a byte string.
+#[cfg(feature = "std")]
+pub fn decode_ascii_words_vec<W: Word>(
+    hex: &[u8],
+    endian: Endian,
+) -> Result<std::vec::Vec<W>, FromHexError> {
+    decode_ascii_words_vec_with_options(hex, endian, DecodeOptions::default())
+}
+
+/// Like [`decode_ascii_words_vec`], but with the given options.
+#[cfg(feature = "std")]
+pub fn decode_ascii_words_vec_with_options<W: Word>(
+    hex: &[u8],
+    endian: Endian,
+    options: DecodeOptions,
+) -> Result<std::vec::Vec<W>, FromHexError> {
+    let mut words = std::vec::Vec::with_capacity(hex.len() / (2 * W::SIZE));
+    decode_words_internal(
+        HexDecodeAsciiIterator::new_with_options(
+            fallible_iterator::convert(hex.iter().copied().enumerate().map(Ok)),
+            options,
+        )
+        .with_padding(needs_padding(hex.iter().map(|&b| b as char), &options)),
+        HexDecodeAsciiIterator::byte_position,
+        endian,
+        |word| {
+            words.push(word);
+            true
+        },
+    )?;
+    Ok(words)
+}
@@ -0,0 +1,336 @@
+//! Reading `xxd` and `hexdump -C` output back into bytes, like `xxd -r`.
+
+use fallible_iterator::FallibleIterator;
+
+use crate::decode::{DecodeOptions, FromHexError, FromHexErrorKind, PrefixPolicy};
+use crate::line_error::{LineError, LineErrorKind};
+use crate::HexDecodeIterator;
+
+/// Represents an error encountered while decoding a hex dump
+pub type FromDumpError = LineError<FromDumpErrorKind>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FromDumpErrorKind {
+    /// The hex bytes of a line are malformed.
+    Hex(FromHexErrorKind),
+    /// A line does not start with a well-formed offset.
+    InvalidOffset,
+    /// The offset of a line does not continue where the previous line ended.
+    UnexpectedOffset { expected: u64, found: u64 },
+    /// A `*` line cannot be expanded: there is no line to repeat, no offset after it,
+    /// or the gap to the next offset is not a multiple of the repeated line.
+    UnresolvedRepeat,
+    /// The data would be longer than the maximum length,
+    /// see [`with_max_len`](DumpDecodeIterator::with_max_len).
+    TooLong,
+}
+
+impl core::fmt::Display for FromDumpErrorKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            FromDumpErrorKind::Hex(kind) => write!(f, "{kind}"),
+            FromDumpErrorKind::InvalidOffset => write!(f, "invalid offset"),
+            FromDumpErrorKind::UnexpectedOffset { expected, found } => {
+                write!(f, "expected offset 0x{expected:x}, found 0x{found:x}")
+            }
+            FromDumpErrorKind::UnresolvedRepeat => write!(f, "unresolved repeat marker"),
+            FromDumpErrorKind::TooLong => write!(f, "data too long"),
+        }
+    }
+}
+
+impl LineErrorKind for FromDumpErrorKind {
+    const FORMAT: &'static str = "hex dump";
+}
+
+/// The hex bytes of a single line
+#[derive(Debug, Clone, Copy)]
+struct Region<'a> {
+    /// 0-based line index
+    line: usize,
+    /// the whole line, for computing columns
+    text: &'a str,
+    /// byte offset of the hex bytes within the line
+    start: usize,
+    end: usize,
+    /// number of bytes on the line
+    len: u64,
+}
+
+type Chars<'a> = fallible_iterator::Convert<
+    core::iter::Map<
+        core::str::CharIndices<'a>,
+        fn((usize, char)) -> Result<(usize, char), FromHexError>,
+    >,
+>;
+
+impl<'a> Region<'a> {
+    fn options() -> DecodeOptions {
+        DecodeOptions::new()
+            .with_prefix(PrefixPolicy::Forbidden)
+            .with_separators(" ")
+    }
+
+    fn bytes(&self) -> HexDecodeIterator<Chars<'a>> {
+        let chars: Chars<'a> = fallible_iterator::convert(
+            self.text[self.start..self.end]
+                .char_indices()
+                .map(Ok as fn(_) -> _),
+        );
+        HexDecodeIterator::new_with_options(chars, Self::options())
+    }
+
+    fn error(&self, err: FromHexError) -> FromDumpError {
+        FromDumpError::at(
+            self.line,
+            self.text,
+            self.start + err.position,
+            FromDumpErrorKind::Hex(err.kind),
+        )
+    }
+}
+
+/// A single parsed line of a hex dump
+enum Line<'a> {
+    Empty,
+    Repeat,
+    /// An offset on its own (e.g. the total length at the end of `hexdump -C` output)
+    End(u64),
+    Data(u64, Region<'a>),
+}
+
+fn parse_line(line: usize, text: &str) -> Result<Line<'_>, FromDumpError> {
+    let content = text.trim_end();
+    if content.trim_start().is_empty() {
+        return Ok(Line::Empty);
+    }
+    if content.trim_start() == "*" {
+        return Ok(Line::Repeat);
+    }
+    let offset_end = content
+        .find(|c: char| !c.is_ascii_hexdigit())
+        .unwrap_or(content.len());
+    let offset = u64::from_str_radix(&content[..offset_end], 16)
+        .map_err(|_| FromDumpError::at(line, text, 0, FromDumpErrorKind::InvalidOffset))?;
+    let rest = &content[offset_end..];
+    let (start, end) = if rest.is_empty() {
+        return Ok(Line::End(offset));
+    } else if let Some(hex) = rest.strip_prefix(':') {
+        // xxd: hex groups separated by single spaces, two spaces before the text column
+        let hex = hex.strip_prefix(' ').unwrap_or(hex);
+        let start = content.len() - hex.len();
+        (start, start + hex.find("  ").unwrap_or(hex.len()))
+    } else if rest.starts_with(' ') {
+        // hexdump -C: hex bytes separated by one or two spaces, text column between '|'
+        let start = offset_end;
+        (start, start + rest.find('|').unwrap_or(rest.len()))
+    } else {
+        return Err(FromDumpError::at(
+            line,
+            text,
+            offset_end,
+            FromDumpErrorKind::InvalidOffset,
+        ));
+    };
+    let mut region = Region {
+        line,
+        text,
+        start,
+        end,
+        len: 0,
+    };
+    // validate the line and count its bytes
+    region.len = region.bytes().count().map_err(|e| region.error(e))? as u64;
+    Ok(Line::Data(offset, region))
+}
+
+/// Fallible iterator that decodes `xxd` or `hexdump -C` output one byte at a time.
+///
+/// Each line consists of an offset, the hex bytes, and an optional text column which is ignored.
+/// The offset is followed by a colon in the `xxd` format; in the `hexdump -C` format, the text
+/// column is enclosed in `|`.
+/// A line consisting only of `*` repeats the previous line until the offset of the next line,
+/// and a line consisting only of an offset marks the end of the data.
+/// Offsets must continue exactly where the previous line ended.
+/// Since a `*` line can stand for any amount of data, the data may be at most
+/// [`with_max_len`](Self::with_max_len) bytes long (16 MiB by default).
+///
+/// Does not allocate.
+///
+/// # Example
+///
+/// ```
+/// use fallible_iterator::FallibleIterator;
+/// use hexhex_impl::*;
+/// let dump = "00000000: 6865 6c6c 6f0a  hello.\n";
+/// let iter = DumpDecodeIterator::new(dump);
+/// let bytes: Vec<u8> = iter.iterator().collect::<Result<_, _>>().unwrap();
+/// assert_eq!(bytes, b"hello\n");
+/// ```
+pub struct DumpDecodeIterator<'a> {
+    lines: core::iter::Enumerate<core::str::Lines<'a>>,
+    /// offset of the byte after the last parsed line
+    offset: u64,
+    /// the last line with data, for repeating
+    previous: Option<Region<'a>>,
+    /// 0-based line index of a pending `*`
+    repeat: Option<usize>,
+    /// line currently being emitted, and how many more times it will be emitted after this one
+    current: Option<(HexDecodeIterator<Chars<'a>>, Region<'a>, u64)>,
+    /// line to be emitted after the current one
+    queued: Option<Region<'a>>,
+    max_len: u64,
+}
+
+impl<'a> DumpDecodeIterator<'a> {
+    /// Construct a new DumpDecodeIterator from the text of a hex dump.
+    pub fn new(dump: &'a str) -> Self {
+        Self {
+            lines: dump.lines().enumerate(),
+            offset: 0,
+            previous: None,
+            repeat: None,
+            current: None,
+            queued: None,
+            max_len: 1 << 24,
+        }
+    }
+
+    /// Limit the length of the data, so that a `*` line cannot expand to too much
+    pub fn with_max_len(mut self, max_len: u64) -> Self {
+        self.max_len = max_len;
+        self
+    }
+
+    /// Check that the given offset continues the data, expanding a pending `*` if needed.
+    /// Returns the number of times the previous line must be repeated.
+    fn catch_up(&mut self, found: u64, line: usize, text: &str) -> Result<u64, FromDumpError> {
+        let expected = self.offset;
+        let repeat = self.repeat.take();
+        if found > self.max_len {
+            return Err(FromDumpError::at(line, text, 0, FromDumpErrorKind::TooLong));
+        }
+        if found < expected || (found > expected && repeat.is_none()) {
+            return Err(FromDumpError::at(
+                line,
+                text,
+                0,
+                FromDumpErrorKind::UnexpectedOffset { expected, found },
+            ));
+        }
+        if found == expected {
+            return Ok(0);
+        }
+        match self.previous {
+            Some(region) if (found - expected).checked_rem(region.len) == Some(0) => {
+                Ok((found - expected) / region.len)
+            }
+            _ => Err(FromDumpError::at(
+                line,
+                text,
+                0,
+                FromDumpErrorKind::UnresolvedRepeat,
+            )),
+        }
+    }
+
+    fn emit(&mut self, region: Region<'a>, repetitions: u64) {
+        if repetitions > 0 {
+            self.current = Some((region.bytes(), region, repetitions - 1));
+        }
+    }
+}
+
+impl<'a> FallibleIterator for DumpDecodeIterator<'a> {
+    type Item = u8;
+    type Error = FromDumpError;
+
+    fn next(&mut self) -> Result<Option<u8>, FromDumpError> {
+        loop {
+            if let Some((bytes, region, remaining)) = &mut self.current {
+                match bytes.next() {
+                    Ok(Some(byte)) => return Ok(Some(byte)),
+                    Ok(None) if *remaining > 0 => {
+                        *bytes = region.bytes();
+                        *remaining -= 1;
+                    }
+                    Ok(None) => self.current = None,
+                    Err(e) => return Err(region.error(e)),
+                }
+                continue;
+            }
+            if let Some(region) = self.queued.take() {
+                self.emit(region, 1);
+                continue;
+            }
+            let Some((line, text)) = self.lines.next() else {
+                return match self.repeat {
+                    Some(line) => Err(FromDumpError {
+                        line: line + 1,
+                        column: 1,
+                        kind: FromDumpErrorKind::UnresolvedRepeat,
+                    }),
+                    None => Ok(None),
+                };
+            };
+            match parse_line(line, text)? {
+                Line::Empty => {}
+                Line::Repeat => {
+                    if self.previous.is_none() {
+                        return Err(FromDumpError::at(
+                            line,
+                            text,
+                            0,
+                            FromDumpErrorKind::UnresolvedRepeat,
+                        ));
+                    }
+                    self.repeat = Some(line);
+                }
+                Line::End(offset) => {
+                    let repetitions = self.catch_up(offset, line, text)?;
+                    if let Some(previous) = self.previous {
+                        self.emit(previous, repetitions);
+                    }
+                    self.offset = offset;
+                }
+                Line::Data(offset, region) => {
+                    let repetitions = self.catch_up(offset, line, text)?;
+                    if let Some(previous) = self.previous {
+                        self.emit(previous, repetitions);
+                    }
+                    self.offset = offset.checked_add(region.len).ok_or_else(|| {
+                        FromDumpError::at(line, text, 0, FromDumpErrorKind::InvalidOffset)
+                    })?;
+                    if self.offset > self.max_len {
+                        return Err(FromDumpError::at(line, text, 0, FromDumpErrorKind::TooLong));
+                    }
+                    self.previous = Some(region);
+                    self.queued = Some(region);
+                }
+            }
+        }
+    }
+}
+
+/// Decode `xxd` or `hexdump -C` output, like `xxd -r`.
+///
+/// See [`DumpDecodeIterator`] for the accepted format and the limit on the length of the data.
+///
+/// # Example
+///
+/// ```
+/// use hexhex_impl::*;
+/// let dump = "\
+/// 00000000  00 00 00 00 00 00 00 00  00 00 00 00 00 00 00 00  |................|
+/// *
+/// 00000020  61 62 63                                          |abc|
+/// 00000023
+/// ";
+/// let bytes = decode_dump(dump).unwrap();
+/// assert_eq!(bytes.len(), 35);
+/// assert_eq!(&bytes[32..], b"abc");
+/// ```
+#[cfg(feature = "std")]
+pub fn decode_dump(dump: &str) -> Result<std::vec::Vec<u8>, FromDumpError> {
+    DumpDecodeIterator::new(dump).collect()
+}
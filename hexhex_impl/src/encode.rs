@@ -1,3 +1,5 @@
+use crate::Endian;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 pub enum Case {
     #[default]
@@ -16,6 +18,10 @@ pub struct DisplayOptions {
 }
 
 /// Wrapper struct to display bytes as hex
+///
+/// Formatting goes through `core::fmt`, whose internal buffers are not wiped (not even with the `zeroize` feature),
+/// and neither is whatever the formatter writes to. For secrets, encode with [`ct::encode`](crate::ct::encode)
+/// or [`encode_in_place`] into a buffer that is wiped, such as a `zeroize::Zeroizing` array.
 #[derive(Debug, Clone, Copy)]
 pub struct Hex<T> {
     options: DisplayOptions,
@@ -79,3 +85,205 @@ impl<T> Hex<T> {
 pub fn hex<T: AsRef<[u8]>>(data: T) -> Hex<T> {
     Hex::new(data)
 }
+
+/// Wrapper struct to display bytes as hex words, like `xxd -g` (or `xxd -e` for little endian) and `od -x`
+///
+/// The bytes are grouped into words of [`with_word_size`](HexWords::with_word_size) bytes (4 by default),
+/// and the digits of each word are in the given byte order, so that little-endian words read as numbers.
+/// A shorter last word is displayed the same way.
+/// Like for [`Hex`], no buffers are wiped after formatting.
+///
+/// # Example
+///
+/// ```
+/// use hexhex_impl::*;
+/// let bytes = [0x78, 0x56, 0x34, 0x12, 0xef, 0xbe];
+/// assert_eq!(hex_words(&bytes).to_string(), "78563412 efbe");
+/// assert_eq!(hex_words(&bytes).with_endian(Endian::Little).to_string(), "12345678 beef");
+/// assert_eq!(
+///     hex_words(&bytes).with_word_size(2).with_separator(":").with_case(Case::Upper).to_string(),
+///     "7856:3412:EFBE"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct HexWords<'s, T> {
+    data: T,
+    word_size: usize,
+    endian: Endian,
+    separator: &'s str,
+    case: Case,
+}
+
+impl<T: AsRef<[u8]>> core::fmt::Display for HexWords<'_, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        let digits = digits(self.case);
+        let write_byte = |f: &mut core::fmt::Formatter, byte: &u8| {
+            core::fmt::Write::write_char(f, digits[usize::from(byte >> 4)] as char)?;
+            core::fmt::Write::write_char(f, digits[usize::from(byte & 0xf)] as char)
+        };
+        for (i, word) in self.data.as_ref().chunks(self.word_size).enumerate() {
+            if i > 0 {
+                f.write_str(self.separator)?;
+            }
+            match self.endian {
+                Endian::Big => word.iter().try_for_each(|byte| write_byte(f, byte))?,
+                Endian::Little => word.iter().rev().try_for_each(|byte| write_byte(f, byte))?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T: AsRef<[u8]>> HexWords<'static, T> {
+    /// Create a new wrapper struct to display the content of data as big-endian 4-byte words,
+    /// separated by spaces, in lower case.
+    pub fn new(data: T) -> Self {
+        Self {
+            data,
+            word_size: 4,
+            endian: Endian::Big,
+            separator: " ",
+            case: Case::Lower,
+        }
+    }
+}
+
+impl<'s, T> HexWords<'s, T> {
+    /// Group the bytes into words of `word_size` bytes, typically 2, 4, or 8 (at least 1)
+    pub fn with_word_size(mut self, word_size: usize) -> Self {
+        self.word_size = word_size.max(1);
+        self
+    }
+
+    /// Display the digits of each word in the given byte order
+    pub fn with_endian(mut self, endian: Endian) -> Self {
+        self.endian = endian;
+        self
+    }
+
+    /// Display `separator` between words
+    pub fn with_separator<'t>(self, separator: &'t str) -> HexWords<'t, T> {
+        HexWords {
+            data: self.data,
+            word_size: self.word_size,
+            endian: self.endian,
+            separator,
+            case: self.case,
+        }
+    }
+
+    /// Display in lower or upper case
+    pub fn with_case(mut self, case: Case) -> Self {
+        self.case = case;
+        self
+    }
+}
+
+/// Shorthand for [`HexWords::new`]
+pub fn hex_words<T: AsRef<[u8]>>(data: T) -> HexWords<'static, T> {
+    HexWords::new(data)
+}
+
+/// The number of hex digits that `len` bytes encode to (without prefix), for sizing buffers.
+///
+/// # Example
+///
+/// ```
+/// use hexhex_impl::*;
+/// const KEY: [u8; 4] = [0xde, 0xad, 0xbe, 0xef];
+/// const KEY_HEX: [u8; encoded_len(KEY.len())] = encode_array(&KEY, Case::Lower);
+/// assert_eq!(&KEY_HEX, b"deadbeef");
+/// ```
+pub const fn encoded_len(len: usize) -> usize {
+    2 * len
+}
+
+pub(crate) const fn digits(case: Case) -> &'static [u8; 16] {
+    match case {
+        Case::Lower => b"0123456789abcdef",
+        Case::Upper => b"0123456789ABCDEF",
+    }
+}
+
+/// Encode bytes as hex into an array of ASCII digits, in a const context if needed.
+///
+/// `M` must be twice `N`; this is checked when the function is evaluated,
+/// which is a compile time error in a const context.
+/// Use [`encoded_str`] to turn the result into a `&str`.
+///
+/// # Example
+///
+/// ```
+/// use hexhex_impl::*;
+/// const FINGERPRINT: [u8; 4] = [0xde, 0xad, 0xbe, 0xef];
+/// const FINGERPRINT_HEX: [u8; 8] = encode_array(&FINGERPRINT, Case::Upper);
+/// assert_eq!(&FINGERPRINT_HEX, b"DEADBEEF");
+/// ```
+///
+/// ```compile_fail
+/// use hexhex_impl::*;
+/// const TOO_SHORT: [u8; 3] = encode_array(&[0xde, 0xad], Case::Lower);
+/// # let _ = TOO_SHORT;
+/// ```
+pub const fn encode_array<const N: usize, const M: usize>(bytes: &[u8; N], case: Case) -> [u8; M] {
+    assert!(
+        M == 2 * N,
+        "output array must be twice as long as the input"
+    );
+    let digits = digits(case);
+    let mut hex = [0u8; M];
+    let mut i = 0;
+    while i < N {
+        hex[2 * i] = digits[(bytes[i] >> 4) as usize];
+        hex[2 * i + 1] = digits[(bytes[i] & 0xf) as usize];
+        i += 1;
+    }
+    hex
+}
+
+/// View the output of [`encode_array`] as a `&str`, in a const context if needed.
+///
+/// Panics if `hex` is not valid UTF-8, which cannot happen for the output of [`encode_array`].
+///
+/// # Example
+///
+/// ```
+/// use hexhex_impl::*;
+/// const FIRMWARE_ID: [u8; 2] = [0xc0, 0xde];
+/// static FIRMWARE_ID_HEX: [u8; 4] = encode_array(&FIRMWARE_ID, Case::Lower);
+/// static FIRMWARE_ID_STR: &str = encoded_str(&FIRMWARE_ID_HEX);
+/// assert_eq!(FIRMWARE_ID_STR, "c0de");
+/// ```
+pub const fn encoded_str(hex: &[u8]) -> &str {
+    match core::str::from_utf8(hex) {
+        Ok(s) => s,
+        Err(_) => panic!("encoded hex is not valid UTF-8"),
+    }
+}
+
+/// Encode the bytes at the start of `buf` as hex, filling the whole buffer.
+///
+/// The first half of `buf` holds the bytes, the rest is room for the digits.
+/// They are written back to front, so every byte is read before it is overwritten.
+/// If the length of `buf` is odd, its last byte is left untouched and not part of the result.
+///
+/// Does not use the `core::fmt` machinery.
+///
+/// # Example
+///
+/// ```
+/// use hexhex_impl::*;
+/// let mut buf = [0u8; 8];
+/// buf[..4].copy_from_slice(&[0xde, 0xad, 0xbe, 0xef]);
+/// assert_eq!(encode_in_place(&mut buf, Case::Upper), "DEADBEEF");
+/// ```
+pub fn encode_in_place(buf: &mut [u8], case: Case) -> &str {
+    let digits = digits(case);
+    let len = buf.len() / 2;
+    for i in (0..len).rev() {
+        let byte = buf[i];
+        buf[2 * i] = digits[(byte >> 4) as usize];
+        buf[2 * i + 1] = digits[(byte & 0xf) as usize];
+    }
+    encoded_str(&buf[..2 * len])
+}
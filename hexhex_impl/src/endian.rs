@@ -0,0 +1,70 @@
+/// Byte order of multi-byte words
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Endian {
+    /// Most significant byte first
+    #[default]
+    Big,
+    /// Least significant byte first
+    Little,
+}
+
+/// Unsigned integer types that hex can be decoded into as words, see [`decode_words`](crate::decode_words)
+///
+/// Implemented for `u8`, `u16`, `u32`, `u64`, and `u128`.
+pub trait Word: Copy + private::Sealed {
+    /// The number of bytes in a word
+    const SIZE: usize;
+
+    /// The word made up of `bytes` (exactly [`SIZE`](Word::SIZE) of them) in the given byte order
+    #[doc(hidden)]
+    fn from_bytes(bytes: &[u8], endian: Endian) -> Self;
+}
+
+mod private {
+    pub trait Sealed {}
+}
+
+macro_rules! impl_word {
+    ($($t:ty),*) => {
+        $(
+            impl private::Sealed for $t {}
+
+            impl Word for $t {
+                const SIZE: usize = core::mem::size_of::<$t>();
+
+                fn from_bytes(bytes: &[u8], endian: Endian) -> Self {
+                    let mut array = [0u8; core::mem::size_of::<$t>()];
+                    array.copy_from_slice(bytes);
+                    match endian {
+                        Endian::Big => <$t>::from_be_bytes(array),
+                        Endian::Little => <$t>::from_le_bytes(array),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_word!(u8, u16, u32, u64, u128);
+
+#[cfg(feature = "std")]
+impl Endian {
+    /// The lowest `len` bytes of `word` in this byte order, in the first `len` bytes of the result.
+    pub(crate) fn word_to_bytes(self, word: u64, len: usize) -> [u8; 8] {
+        let mut bytes = [0u8; 8];
+        match self {
+            Endian::Big => bytes[..len].copy_from_slice(&word.to_be_bytes()[8 - len..]),
+            Endian::Little => bytes[..len].copy_from_slice(&word.to_le_bytes()[..len]),
+        }
+        bytes
+    }
+
+    /// The word made up of `bytes` in this byte order (at most 8 bytes).
+    pub(crate) fn bytes_to_word(self, bytes: &[u8]) -> u64 {
+        let fold = |word: u64, b: &u8| word << 8 | u64::from(*b);
+        match self {
+            Endian::Big => bytes.iter().fold(0, fold),
+            Endian::Little => bytes.iter().rev().fold(0, fold),
+        }
+    }
+}
@@ -0,0 +1,312 @@
+//! Hexadecimal floating-point numbers, as written by C's `%a` format and read by `strtod`
+//!
+//! A hex float like `0x1.8p+3` is a hex mantissa with an optional fraction, followed by a
+//! decimal power of two: 1.5 × 2³ = 12. Unlike decimal notation, it represents every finite
+//! `f32` and `f64` exactly, so values can be exchanged with C tooling without rounding errors.
+//!
+//! [`HexFloat`] formats like `printf("%a")` (or `%A` in upper case, except for the `0x` prefix), including subnormals,
+//! infinities (`inf`) and NaN (`nan`). [`parse_f64`] and [`parse_f32`] read them back,
+//! rounding to nearest, ties to even, if there are more digits than fit.
+//!
+//! # Example
+//!
+//! ```
+//! use hexhex_impl::float::{hex_float, parse_f64};
+//! assert_eq!(hex_float(12.0).to_string(), "0x1.8p+3");
+//! assert_eq!(parse_f64("0x1.8p+3").unwrap(), 12.0);
+//! assert_eq!(parse_f64("-0x1p-1").unwrap(), -0.5);
+//! ```
+
+use crate::decode::common::{check_prefix_policy, has_prefix};
+use crate::decode::{DecodeOptions, FromHexError, FromHexErrorKind};
+use crate::encode::{digits, Case};
+
+/// Wrapper struct to display a floating-point number in hex, like C's `%a`
+///
+/// An `f32` is displayed as the equal `f64`, as C does when passing it to `printf`.
+///
+/// # Example
+///
+/// ```
+/// use hexhex_impl::float::HexFloat;
+/// use hexhex_impl::Case;
+/// assert_eq!(HexFloat::new(0.1).to_string(), "0x1.999999999999ap-4");
+/// assert_eq!(HexFloat::from(0.1f32).to_string(), "0x1.99999ap-4");
+/// assert_eq!(HexFloat::new(-f64::INFINITY).with_case(Case::Upper).to_string(), "-INF");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct HexFloat {
+    value: f64,
+    case: Case,
+}
+
+impl HexFloat {
+    /// Create a new wrapper struct to display `value` in hex, in lower case.
+    pub fn new(value: f64) -> Self {
+        Self {
+            value,
+            case: Case::Lower,
+        }
+    }
+
+    /// Display in lower case (like `%a`) or upper case (like `%A`, but the `0x` prefix stays lower case)
+    pub fn with_case(mut self, case: Case) -> Self {
+        self.case = case;
+        self
+    }
+}
+
+impl From<f64> for HexFloat {
+    fn from(value: f64) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<f32> for HexFloat {
+    fn from(value: f32) -> Self {
+        Self::new(value.into())
+    }
+}
+
+/// Shorthand for [`HexFloat::from`]
+pub fn hex_float(value: impl Into<HexFloat>) -> HexFloat {
+    value.into()
+}
+
+impl core::fmt::Display for HexFloat {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        let upper = self.case == Case::Upper;
+        let bits = self.value.to_bits();
+        let exponent = (bits >> 52) & 0x7ff;
+        let mantissa = bits & ((1 << 52) - 1);
+        if bits >> 63 != 0 {
+            f.write_str("-")?;
+        }
+        let (lead, exponent) = match exponent {
+            0x7ff => {
+                return f.write_str(match (mantissa == 0, upper) {
+                    (true, false) => "inf",
+                    (true, true) => "INF",
+                    (false, false) => "nan",
+                    (false, true) => "NAN",
+                });
+            }
+            0 if mantissa == 0 => ('0', 0),
+            // subnormal
+            0 => ('0', -1022),
+            _ => ('1', exponent as i64 - 1023),
+        };
+        // the prefix is always lower case, as for `Hex` and when decoding
+        f.write_str("0x")?;
+        core::fmt::Write::write_char(f, lead)?;
+        if mantissa != 0 {
+            f.write_str(".")?;
+            // 13 digits, without trailing zeros
+            let digits = digits(self.case);
+            let len = 13 - mantissa.trailing_zeros() / 4;
+            for i in 0..len {
+                let nibble = (mantissa >> (48 - 4 * i)) & 0xf;
+                core::fmt::Write::write_char(f, digits[nibble as usize] as char)?;
+            }
+        }
+        write!(f, "{}{exponent:+}", if upper { 'P' } else { 'p' })
+    }
+}
+
+/// Bit layout of a binary floating-point format
+struct Format {
+    mantissa_bits: u32,
+    exponent_bits: u32,
+}
+
+const F32: Format = Format {
+    mantissa_bits: 23,
+    exponent_bits: 8,
+};
+
+const F64: Format = Format {
+    mantissa_bits: 52,
+    exponent_bits: 11,
+};
+
+/// Parse a hex float into an `f64`.
+///
+/// Accepts what C's `strtod` accepts in hex: an optional sign, the `0x` prefix,
+/// hex digits with an optional `.`, and an optional `p` (or `P`) followed by a signed decimal exponent,
+/// as well as `inf`, `infinity`, and `nan` in any case. As for the other decode functions,
+/// the prefix is lower case only, and optional by default.
+///
+/// Values too small for the format round to zero; values too large are an [`Overflow`](FromHexErrorKind::Overflow) error
+/// at the `p` of the exponent, or at the last digit if there is no exponent.
+///
+/// # Example
+///
+/// ```
+/// use hexhex_impl::float::parse_f64;
+/// use hexhex_impl::FromHexErrorKind;
+/// assert_eq!(parse_f64("0x1.999999999999ap-4").unwrap(), 0.1);
+/// assert_eq!(parse_f64("1.8p1").unwrap(), 3.0);
+/// let err = parse_f64("0x1.8q").unwrap_err();
+/// assert_eq!((err.position(), err.kind()), (5, FromHexErrorKind::UnexpectedCharacter('q')));
+/// ```
+pub fn parse_f64(hex: &str) -> Result<f64, FromHexError> {
+    parse_f64_with_options(hex, DecodeOptions::default())
+}
+
+/// Like [`parse_f64`], but with the given options. Only the prefix policy applies.
+pub fn parse_f64_with_options(hex: &str, options: DecodeOptions) -> Result<f64, FromHexError> {
+    parse(hex, options, &F64).map(f64::from_bits)
+}
+
+/// Like [`parse_f64`], but for an `f32`, which is rounded only once.
+pub fn parse_f32(hex: &str) -> Result<f32, FromHexError> {
+    parse_f32_with_options(hex, DecodeOptions::default())
+}
+
+/// Like [`parse_f32`], but with the given options. Only the prefix policy applies.
+pub fn parse_f32_with_options(hex: &str, options: DecodeOptions) -> Result<f32, FromHexError> {
+    // the bits of an f32 fit into its u32
+    parse(hex, options, &F32).map(|bits| f32::from_bits(bits as u32))
+}
+
+fn error(position: usize, kind: FromHexErrorKind) -> FromHexError {
+    FromHexError { position, kind }
+}
+
+/// Digits are expected at `position`, but there are none
+fn missing_digits(hex: &str, position: usize) -> FromHexError {
+    match hex[position..].chars().next() {
+        Some(c) => error(position, FromHexErrorKind::UnexpectedCharacter(c)),
+        None => error(position, FromHexErrorKind::MissingDigits),
+    }
+}
+
+/// Parse a hex float into the bits of the given format
+fn parse(hex: &str, options: DecodeOptions, format: &Format) -> Result<u64, FromHexError> {
+    let bytes = hex.as_bytes();
+    let mut position = match bytes.first() {
+        Some(b'+' | b'-') => 1,
+        _ => 0,
+    };
+    let sign =
+        u64::from(bytes.first() == Some(&b'-')) << (format.mantissa_bits + format.exponent_bits);
+    let infinity = ((1 << format.exponent_bits) - 1) << format.mantissa_bits;
+    let rest = &hex[position..];
+    if rest.eq_ignore_ascii_case("inf") || rest.eq_ignore_ascii_case("infinity") {
+        return Ok(sign | infinity);
+    }
+    if rest.eq_ignore_ascii_case("nan") {
+        return Ok(sign | infinity | 1 << (format.mantissa_bits - 1));
+    }
+    let prefix = has_prefix(hex[position..].chars());
+    check_prefix_policy(prefix, options.prefix).map_err(|kind| error(position, kind))?;
+    if prefix {
+        position += 2;
+    }
+
+    // the first 16 significant digits, and whether any of the others are nonzero
+    let mut mantissa = 0u64;
+    let mut sticky = false;
+    // value = mantissa * 2^exponent
+    let mut exponent = 0i64;
+    let mut digits = 0;
+    let mut last_digit = position;
+    let mut point = false;
+    while let Some(&c) = bytes.get(position) {
+        let digit = match c {
+            b'.' if !point => {
+                point = true;
+                position += 1;
+                continue;
+            }
+            b'0'..=b'9' => c - b'0',
+            b'a'..=b'f' => c - b'a' + 10,
+            b'A'..=b'F' => c - b'A' + 10,
+            _ => break,
+        };
+        digits += 1;
+        last_digit = position;
+        if mantissa >> 60 == 0 {
+            mantissa = mantissa << 4 | u64::from(digit);
+            exponent -= if point { 4 } else { 0 };
+        } else {
+            sticky |= digit != 0;
+            exponent += if point { 0 } else { 4 };
+        }
+        position += 1;
+    }
+    if digits == 0 {
+        return Err(missing_digits(hex, position));
+    }
+
+    // where an overflow is reported: the exponent if there is one, otherwise the last digit
+    let mut overflow_position = last_digit;
+    if let Some(b'p' | b'P') = bytes.get(position) {
+        overflow_position = position;
+        position += 1;
+        let negative = bytes.get(position) == Some(&b'-');
+        if let Some(b'+' | b'-') = bytes.get(position) {
+            position += 1;
+        }
+        let start = position;
+        let mut power = 0i64;
+        while let Some(&c @ b'0'..=b'9') = bytes.get(position) {
+            // anything this large over- or underflows anyway
+            power = (power * 10 + i64::from(c - b'0')).min(1 << 32);
+            position += 1;
+        }
+        if position == start {
+            return Err(missing_digits(hex, position));
+        }
+        exponent += if negative { -power } else { power };
+    }
+    if let Some(c) = hex[position..].chars().next() {
+        return Err(error(position, FromHexErrorKind::UnexpectedCharacter(c)));
+    }
+
+    if mantissa == 0 {
+        return Ok(sign);
+    }
+    round(mantissa, sticky, exponent, format)
+        .map(|bits| sign | bits)
+        .ok_or(error(overflow_position, FromHexErrorKind::Overflow))
+}
+
+/// The bits of `mantissa * 2^exponent` (plus a bit more if `sticky`) in the given format,
+/// rounded to nearest, ties to even, or `None` if it is too large
+fn round(mantissa: u64, sticky: bool, exponent: i64, format: &Format) -> Option<u64> {
+    let precision = i64::from(format.mantissa_bits) + 1;
+    let bias = (1 << (format.exponent_bits - 1)) - 1;
+    let min_exponent = 1 - bias;
+    let top = 63 - i64::from(mantissa.leading_zeros());
+    // value = 1.xxx * 2^exponent
+    let mut exponent = exponent + top;
+    // the number of mantissa bits that do not fit, which is more for subnormals
+    let drop = top - (precision - 1) + (min_exponent - exponent).max(0);
+    let mut kept = if drop <= 0 {
+        mantissa << -drop
+    } else if drop >= 128 {
+        0
+    } else {
+        let mantissa = u128::from(mantissa);
+        let kept = mantissa >> drop;
+        let rest = mantissa & ((1 << drop) - 1);
+        let half = 1 << (drop - 1);
+        let up = rest > half || (rest == half && (sticky || kept % 2 == 1));
+        // kept has at most `precision` bits
+        (kept + u128::from(up)) as u64
+    };
+    if exponent < min_exponent {
+        // subnormal, or the smallest normal number if rounding carried into the exponent field
+        return Some(kept);
+    }
+    if kept == 1 << precision {
+        kept >>= 1;
+        exponent += 1;
+    }
+    if exponent > bias {
+        return None;
+    }
+    let biased = (exponent + bias) as u64;
+    Some(biased << format.mantissa_bits | (kept & ((1 << format.mantissa_bits) - 1)))
+}
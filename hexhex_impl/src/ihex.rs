@@ -0,0 +1,517 @@
+//! Reading and writing Intel HEX files
+//!
+//! An Intel HEX file consists of records, one per line, each of which looks like `:LLAAAATTDD..CC`:
+//! a byte count `LL`, a 16-bit address `AAAA`, a record type `TT`, the data `DD..`,
+//! and a checksum `CC`, all in hex.
+//! Extended segment and extended linear address records supply the upper bits of the addresses
+//! of subsequent data records.
+//!
+//! Parsing works without allocating via [`RecordIterator`] and [`DataIterator`].
+//! With the `std` feature, `read` collects a whole file into an `IntelHex` image,
+//! which can be written back out with `IntelHex::write`.
+//!
+//! # Example
+//!
+//! ```
+//! # #[cfg(feature = "std")]
+//! # {
+//! use hexhex_impl::ihex;
+//! let file = "\
+//! :0400000001020304F2
+//! :00000001FF
+//! ";
+//! let ihex = ihex::read(file).unwrap();
+//! assert_eq!(ihex.image.get(2), Some(0x03));
+//! assert_eq!(ihex.to_string(), file);
+//! # }
+//! ```
+
+use fallible_iterator::FallibleIterator;
+
+use crate::decode::{DecodeOptions, FromHexErrorKind, PrefixPolicy};
+use crate::line_error::{LineError, LineErrorKind};
+use crate::{decode_to_buf_with_options, Case, Hex};
+
+#[cfg(feature = "std")]
+use crate::MemoryImage;
+
+pub use crate::record::RecordData;
+
+/// Represents an error encountered while reading an Intel HEX file
+pub type FromIhexError = LineError<FromIhexErrorKind>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FromIhexErrorKind {
+    /// The hex digits of a record are malformed.
+    Hex(FromHexErrorKind),
+    /// A record does not start with `:`.
+    MissingStartCode,
+    /// The number of bytes in a record does not match its byte count.
+    InvalidLength,
+    /// The checksum of a record is wrong.
+    ChecksumMismatch { expected: u8, found: u8 },
+    /// The record type is not one of the six standard types.
+    UnsupportedRecordType(u8),
+    /// The byte count of an end of file, address, or start address record is wrong.
+    InvalidRecord,
+    /// The data of a record extends past the 32-bit address space.
+    AddressOverflow,
+    /// There is no end of file record.
+    MissingEndOfFile,
+    /// There are records after the end of file record.
+    DataAfterEndOfFile,
+}
+
+impl core::fmt::Display for FromIhexErrorKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            FromIhexErrorKind::Hex(kind) => write!(f, "{kind}"),
+            FromIhexErrorKind::MissingStartCode => write!(f, "record does not start with ':'"),
+            FromIhexErrorKind::InvalidLength => write!(f, "record length does not match"),
+            FromIhexErrorKind::ChecksumMismatch { expected, found } => {
+                write!(f, "expected checksum 0x{expected:02x}, found 0x{found:02x}")
+            }
+            FromIhexErrorKind::UnsupportedRecordType(t) => {
+                write!(f, "unsupported record type 0x{t:02x}")
+            }
+            FromIhexErrorKind::InvalidRecord => write!(f, "invalid record"),
+            FromIhexErrorKind::AddressOverflow => write!(f, "address overflow"),
+            FromIhexErrorKind::MissingEndOfFile => write!(f, "missing end of file record"),
+            FromIhexErrorKind::DataAfterEndOfFile => write!(f, "data after end of file record"),
+        }
+    }
+}
+
+impl LineErrorKind for FromIhexErrorKind {
+    const FORMAT: &'static str = "Intel HEX";
+}
+
+/// A single Intel HEX record
+///
+/// Displays as the record's line (without line terminator).
+///
+/// # Example
+///
+/// ```
+/// use hexhex_impl::ihex::{parse_record, Record};
+/// let record = parse_record(":020000040800F2").unwrap();
+/// assert_eq!(record, Record::ExtendedLinearAddress(0x0800));
+/// assert_eq!(record.to_string(), ":020000040800F2");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[allow(clippy::large_enum_variant)] // data records are the common case, and this avoids allocating
+pub enum Record {
+    /// Type `00`: data at a 16-bit offset from the current base address
+    Data { offset: u16, data: RecordData },
+    /// Type `01`: end of file
+    EndOfFile,
+    /// Type `02`: base address for subsequent data is this value times 16
+    ExtendedSegmentAddress(u16),
+    /// Type `03`: start address as a CS:IP pair
+    StartSegmentAddress { cs: u16, ip: u16 },
+    /// Type `04`: base address for subsequent data is this value times 65536
+    ExtendedLinearAddress(u16),
+    /// Type `05`: 32-bit start address
+    StartLinearAddress(u32),
+}
+
+/// Execution start address of an Intel HEX file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StartAddress {
+    /// 16-bit segment and offset (record type `03`)
+    Segment { cs: u16, ip: u16 },
+    /// 32-bit linear address (record type `05`)
+    Linear(u32),
+}
+
+impl core::fmt::Display for Record {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        let mut buf = [0u8; 4];
+        let (offset, kind, data): (u16, u8, &[u8]) = match self {
+            Record::Data { offset, data } => (*offset, 0x00, data),
+            Record::EndOfFile => (0, 0x01, &[]),
+            Record::ExtendedSegmentAddress(segment) => {
+                buf[..2].copy_from_slice(&segment.to_be_bytes());
+                (0, 0x02, &buf[..2])
+            }
+            Record::StartSegmentAddress { cs, ip } => {
+                buf[..2].copy_from_slice(&cs.to_be_bytes());
+                buf[2..].copy_from_slice(&ip.to_be_bytes());
+                (0, 0x03, &buf)
+            }
+            Record::ExtendedLinearAddress(upper) => {
+                buf[..2].copy_from_slice(&upper.to_be_bytes());
+                (0, 0x04, &buf[..2])
+            }
+            Record::StartLinearAddress(address) => {
+                buf.copy_from_slice(&address.to_be_bytes());
+                (0, 0x05, &buf)
+            }
+        };
+        // data is at most 255 bytes by construction
+        let count = data.len() as u8;
+        let [offset_high, offset_low] = offset.to_be_bytes();
+        let sum = data.iter().fold(
+            count
+                .wrapping_add(offset_high)
+                .wrapping_add(offset_low)
+                .wrapping_add(kind),
+            |sum, b| sum.wrapping_add(*b),
+        );
+        write!(
+            f,
+            ":{count:02X}{offset:04X}{kind:02X}{}{:02X}",
+            Hex::new(data).with_case(Case::Upper),
+            sum.wrapping_neg()
+        )
+    }
+}
+
+fn parse_record_at(line: usize, text: &str) -> Result<Record, FromIhexError> {
+    let content = text.trim_end();
+    let Some(hex) = content.strip_prefix(':') else {
+        return Err(FromIhexError::at(
+            line,
+            text,
+            0,
+            FromIhexErrorKind::MissingStartCode,
+        ));
+    };
+    let mut buf = [0u8; 5 + 255];
+    let options = DecodeOptions::new().with_prefix(PrefixPolicy::Forbidden);
+    let len = decode_to_buf_with_options(hex, &mut buf, options).map_err(|e| {
+        FromIhexError::at(line, text, 1 + e.position, FromIhexErrorKind::Hex(e.kind))
+    })?;
+    if len < 5 || len != 5 + buf[0] as usize {
+        return Err(FromIhexError::at(
+            line,
+            text,
+            1,
+            FromIhexErrorKind::InvalidLength,
+        ));
+    }
+    let sum = buf[..len].iter().fold(0u8, |sum, b| sum.wrapping_add(*b));
+    if sum != 0 {
+        let found = buf[len - 1];
+        return Err(FromIhexError::at(
+            line,
+            text,
+            1 + 2 * (len - 1),
+            FromIhexErrorKind::ChecksumMismatch {
+                expected: found.wrapping_sub(sum),
+                found,
+            },
+        ));
+    }
+    let offset = u16::from_be_bytes([buf[1], buf[2]]);
+    let data = &buf[4..len - 1];
+    let record = match (buf[3], data.len()) {
+        (0x00, _) => Record::Data {
+            offset,
+            data: RecordData::new(data).ok_or_else(|| {
+                FromIhexError::at(line, text, 1, FromIhexErrorKind::InvalidLength)
+            })?,
+        },
+        (0x01, 0) => Record::EndOfFile,
+        (0x02, 2) => Record::ExtendedSegmentAddress(u16::from_be_bytes([data[0], data[1]])),
+        (0x03, 4) => Record::StartSegmentAddress {
+            cs: u16::from_be_bytes([data[0], data[1]]),
+            ip: u16::from_be_bytes([data[2], data[3]]),
+        },
+        (0x04, 2) => Record::ExtendedLinearAddress(u16::from_be_bytes([data[0], data[1]])),
+        (0x05, 4) => {
+            Record::StartLinearAddress(u32::from_be_bytes([data[0], data[1], data[2], data[3]]))
+        }
+        (0x01..=0x05, _) => {
+            return Err(FromIhexError::at(
+                line,
+                text,
+                7,
+                FromIhexErrorKind::InvalidRecord,
+            ));
+        }
+        (t, _) => {
+            return Err(FromIhexError::at(
+                line,
+                text,
+                7,
+                FromIhexErrorKind::UnsupportedRecordType(t),
+            ))
+        }
+    };
+    Ok(record)
+}
+
+/// Parse a single Intel HEX record.
+///
+/// Errors are reported on line 1.
+pub fn parse_record(line: &str) -> Result<Record, FromIhexError> {
+    parse_record_at(0, line)
+}
+
+/// Fallible iterator over the records of an Intel HEX file.
+///
+/// Blank lines are skipped.
+/// The end of file record is yielded, and must be the last record.
+///
+/// Does not allocate.
+pub struct RecordIterator<'a> {
+    lines: core::iter::Enumerate<core::str::Lines<'a>>,
+    /// The number of lines read so far
+    lines_read: usize,
+    ended: bool,
+}
+
+impl<'a> RecordIterator<'a> {
+    /// Construct a new RecordIterator from the text of an Intel HEX file.
+    pub fn new(file: &'a str) -> Self {
+        Self {
+            lines: file.lines().enumerate(),
+            lines_read: 0,
+            ended: false,
+        }
+    }
+}
+
+impl FallibleIterator for RecordIterator<'_> {
+    type Item = Record;
+    type Error = FromIhexError;
+
+    fn next(&mut self) -> Result<Option<Record>, FromIhexError> {
+        loop {
+            let Some((line, text)) = self.lines.next() else {
+                if self.ended {
+                    return Ok(None);
+                }
+                // the line after the last one, which is line 1 of an empty file
+                return Err(FromIhexError {
+                    line: self.lines_read + 1,
+                    column: 1,
+                    kind: FromIhexErrorKind::MissingEndOfFile,
+                });
+            };
+            self.lines_read = line + 1;
+            if text.trim().is_empty() {
+                continue;
+            }
+            if self.ended {
+                return Err(FromIhexError::at(
+                    line,
+                    text,
+                    0,
+                    FromIhexErrorKind::DataAfterEndOfFile,
+                ));
+            }
+            let record = parse_record_at(line, text)?;
+            self.ended = record == Record::EndOfFile;
+            return Ok(Some(record));
+        }
+    }
+}
+
+/// Fallible iterator over the data of an Intel HEX file, with absolute addresses.
+///
+/// Resolves extended segment and extended linear addresses, and records the start address.
+/// With segment addresses, offsets wrap around within the 64 KiB segment, so a record that crosses
+/// the end of the segment is returned as two parts, the second one at the start of the segment.
+///
+/// Does not allocate.
+///
+/// # Example
+///
+/// ```
+/// use fallible_iterator::FallibleIterator;
+/// use hexhex_impl::ihex::{DataIterator, StartAddress};
+/// let file = "\
+/// :020000040800F2
+/// :0400100001020304E2
+/// :0400000508000101ED
+/// :00000001FF
+/// ";
+/// let mut data = DataIterator::new(file);
+/// let (address, bytes) = data.next().unwrap().unwrap();
+/// assert_eq!(address, 0x0800_0010);
+/// assert_eq!(&*bytes, &[1, 2, 3, 4]);
+/// assert!(data.next().unwrap().is_none());
+/// assert_eq!(data.start_address(), Some(StartAddress::Linear(0x0800_0101)));
+/// ```
+pub struct DataIterator<'a> {
+    records: RecordIterator<'a>,
+    base: u32,
+    /// whether the base is from an extended segment address
+    segmented: bool,
+    /// the part of a data record after the offset wrapped around
+    wrapped: Option<(u32, RecordData)>,
+    start_address: Option<StartAddress>,
+}
+
+impl<'a> DataIterator<'a> {
+    /// Construct a new DataIterator from the text of an Intel HEX file.
+    pub fn new(file: &'a str) -> Self {
+        Self {
+            records: RecordIterator::new(file),
+            base: 0,
+            segmented: false,
+            wrapped: None,
+            start_address: None,
+        }
+    }
+
+    /// The start address from the records read so far, if any
+    pub fn start_address(&self) -> Option<StartAddress> {
+        self.start_address
+    }
+}
+
+impl FallibleIterator for DataIterator<'_> {
+    type Item = (u32, RecordData);
+    type Error = FromIhexError;
+
+    fn next(&mut self) -> Result<Option<(u32, RecordData)>, FromIhexError> {
+        if let Some(wrapped) = self.wrapped.take() {
+            return Ok(Some(wrapped));
+        }
+        loop {
+            match self.records.next()? {
+                None => return Ok(None),
+                Some(Record::Data { offset, mut data }) => {
+                    let until_wrap = 0x10000 - usize::from(offset);
+                    if self.segmented && data.len() > until_wrap {
+                        let (head, tail) = data.split_at(until_wrap);
+                        self.wrapped = Some((self.base, tail));
+                        data = head;
+                    }
+                    let address = u64::from(self.base) + u64::from(offset);
+                    if address + data.len() as u64 > 1 << 32 {
+                        return Err(FromIhexError {
+                            line: self.records.lines_read,
+                            column: 4,
+                            kind: FromIhexErrorKind::AddressOverflow,
+                        });
+                    }
+                    return Ok(Some((address as u32, data)));
+                }
+                Some(Record::EndOfFile) => {}
+                Some(Record::ExtendedSegmentAddress(segment)) => {
+                    self.base = u32::from(segment) << 4;
+                    self.segmented = true;
+                }
+                Some(Record::ExtendedLinearAddress(upper)) => {
+                    self.base = u32::from(upper) << 16;
+                    self.segmented = false;
+                }
+                Some(Record::StartSegmentAddress { cs, ip }) => {
+                    self.start_address = Some(StartAddress::Segment { cs, ip });
+                }
+                Some(Record::StartLinearAddress(address)) => {
+                    self.start_address = Some(StartAddress::Linear(address));
+                }
+            }
+        }
+    }
+}
+
+/// Options for writing Intel HEX files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WriteOptions {
+    /// Maximum number of data bytes per record (1 to 255; 0 is treated as 1).
+    pub record_length: u8,
+}
+
+impl Default for WriteOptions {
+    fn default() -> Self {
+        Self { record_length: 16 }
+    }
+}
+
+/// The contents of an Intel HEX file
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IntelHex {
+    /// The data
+    pub image: MemoryImage,
+    /// The execution start address, if any
+    pub start_address: Option<StartAddress>,
+}
+
+/// Read an Intel HEX file.
+///
+/// Later records overwrite earlier ones at the same address.
+#[cfg(feature = "std")]
+pub fn read(file: &str) -> Result<IntelHex, FromIhexError> {
+    let mut ihex = IntelHex::default();
+    let mut data = DataIterator::new(file);
+    while let Some((address, bytes)) = data.next()? {
+        ihex.image.insert(address, &bytes);
+    }
+    ihex.start_address = data.start_address();
+    Ok(ihex)
+}
+
+#[cfg(feature = "std")]
+impl IntelHex {
+    /// Write the file with the given options, using extended linear address records where needed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexhex_impl::ihex::{IntelHex, WriteOptions};
+    /// let mut ihex = IntelHex::default();
+    /// ihex.image.insert(0x0001_fffe, &[1, 2, 3, 4]);
+    /// let mut out = String::new();
+    /// ihex.write(&mut out, WriteOptions { record_length: 2 }).unwrap();
+    /// assert_eq!(out, "\
+    /// :020000040001F9
+    /// :02FFFE000102FE
+    /// :020000040002F8
+    /// :020000000304F7
+    /// :00000001FF
+    /// ");
+    /// ```
+    pub fn write<W: core::fmt::Write>(
+        &self,
+        out: &mut W,
+        options: WriteOptions,
+    ) -> core::fmt::Result {
+        let record_length = usize::from(options.record_length.max(1));
+        let mut upper = 0u16;
+        for (start, bytes) in self.image.segments() {
+            let mut address = start;
+            let mut bytes = bytes;
+            while !bytes.is_empty() {
+                let [high, low] = [(address >> 16) as u16, address as u16];
+                if high != upper {
+                    upper = high;
+                    writeln!(out, "{}", Record::ExtendedLinearAddress(upper))?;
+                }
+                let len = bytes
+                    .len()
+                    .min(record_length)
+                    .min(0x10000 - usize::from(low));
+                let (chunk, rest) = bytes.split_at(len);
+                let data = RecordData::new(chunk).ok_or(core::fmt::Error)?;
+                writeln!(out, "{}", Record::Data { offset: low, data })?;
+                bytes = rest;
+                address = address.wrapping_add(len as u32);
+            }
+        }
+        match self.start_address {
+            Some(StartAddress::Segment { cs, ip }) => {
+                writeln!(out, "{}", Record::StartSegmentAddress { cs, ip })?
+            }
+            Some(StartAddress::Linear(address)) => {
+                writeln!(out, "{}", Record::StartLinearAddress(address))?
+            }
+            None => {}
+        }
+        writeln!(out, "{}", Record::EndOfFile)
+    }
+}
+
+/// Writes the file with the default [`WriteOptions`].
+#[cfg(feature = "std")]
+impl core::fmt::Display for IntelHex {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        self.write(f, WriteOptions::default())
+    }
+}
@@ -0,0 +1,92 @@
+use std::collections::BTreeMap;
+use std::vec::Vec;
+
+/// Sparse memory image: runs of bytes at 32-bit addresses
+///
+/// Adjacent and overlapping runs are merged into a single segment; later writes overwrite earlier ones.
+///
+/// # Example
+///
+/// ```
+/// use hexhex_impl::MemoryImage;
+/// let mut image = MemoryImage::new();
+/// image.insert(0x1000, &[1, 2, 3]);
+/// image.insert(0x1003, &[4]);
+/// image.insert(0x2000, &[5]);
+/// let segments: Vec<_> = image.segments().collect();
+/// assert_eq!(segments, [(0x1000, &[1, 2, 3, 4][..]), (0x2000, &[5][..])]);
+/// assert_eq!(image.get(0x1001), Some(2));
+/// assert_eq!(image.get(0x1004), None);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct MemoryImage {
+    segments: BTreeMap<u32, Vec<u8>>,
+}
+
+impl MemoryImage {
+    /// Create a new empty image.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Write data at the given address.
+    ///
+    /// Data that would extend past the end of the 32-bit address space is truncated.
+    pub fn insert(&mut self, address: u32, data: &[u8]) {
+        let start = u64::from(address);
+        let space = usize::try_from((1 << 32) - start).unwrap_or(usize::MAX);
+        let data = &data[..data.len().min(space)];
+        let end = start + data.len() as u64;
+        if data.is_empty() {
+            return;
+        }
+        // segments are sorted and disjoint, so their ends are sorted, too
+        let upper = u32::try_from(end).unwrap_or(u32::MAX);
+        let touching: Vec<u32> = self
+            .segments
+            .range(..=upper)
+            .rev()
+            .take_while(|(&key, bytes)| u64::from(key) + bytes.len() as u64 >= start)
+            .map(|(&key, _)| key)
+            .collect();
+        let merged_start = touching.last().map_or(address, |&key| key.min(address));
+        let mut merged = Vec::new();
+        for key in touching.into_iter().rev() {
+            let bytes = self.segments.remove(&key).unwrap_or_default();
+            let offset = (key - merged_start) as usize;
+            if merged.len() < offset + bytes.len() {
+                merged.resize(offset + bytes.len(), 0);
+            }
+            merged[offset..offset + bytes.len()].copy_from_slice(&bytes);
+        }
+        let offset = (address - merged_start) as usize;
+        if merged.len() < offset + data.len() {
+            merged.resize(offset + data.len(), 0);
+        }
+        merged[offset..offset + data.len()].copy_from_slice(data);
+        self.segments.insert(merged_start, merged);
+    }
+
+    /// Read the byte at the given address, if any.
+    pub fn get(&self, address: u32) -> Option<u8> {
+        let (&key, bytes) = self.segments.range(..=address).next_back()?;
+        bytes.get((address - key) as usize).copied()
+    }
+
+    /// Iterate over the contiguous segments in order of their start address.
+    pub fn segments(&self) -> impl Iterator<Item = (u32, &[u8])> {
+        self.segments
+            .iter()
+            .map(|(&address, bytes)| (address, bytes.as_slice()))
+    }
+
+    /// Whether the image contains no data.
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// The total number of bytes in the image.
+    pub fn len(&self) -> usize {
+        self.segments.values().map(Vec::len).sum()
+    }
+}
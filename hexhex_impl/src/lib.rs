@@ -5,18 +5,88 @@
 extern crate std;
 
 mod decode;
+mod dump;
 mod encode;
+mod endian;
+#[cfg(feature = "std")]
+mod image;
+mod line_error;
+mod nibbles;
+mod record;
+
+pub mod ct;
+pub mod float;
+pub mod ihex;
+pub mod readmemh;
+pub mod srec;
 
 #[cfg(test)]
+// the tables in the tests spell out `16 * 1` and the like
+#[allow(clippy::identity_op)]
 mod tests;
 
+pub use decode::array::{decode_array, try_decode_array};
+#[cfg(feature = "zeroize")]
+pub use decode::array::{try_decode_array_zeroizing, try_decode_ascii_array_zeroizing};
 pub use decode::buf::{
-    decode_ascii_to_buf, decode_ascii_to_buf_exact, decode_to_buf, decode_to_buf_exact,
+    decode_ascii_to_buf, decode_ascii_to_buf_exact, decode_ascii_to_buf_exact_with_options,
+    decode_ascii_to_buf_with_options, decode_to_buf, decode_to_buf_exact,
+    decode_to_buf_exact_with_options, decode_to_buf_with_options,
+};
+pub use decode::in_place::{decode_in_place, decode_in_place_with_options};
+#[cfg(feature = "zeroize")]
+pub use decode::in_place::{decode_in_place_zeroizing, decode_in_place_zeroizing_with_options};
+pub use decode::iter::{
+    decode_ascii_iter, decode_ascii_iter_with_options, decode_iter, decode_iter_with_options,
+};
+#[cfg(feature = "bytes")]
+pub use decode::sink::BufMutSink;
+pub use decode::sink::{
+    decode_ascii_into, decode_ascii_into_with_options, decode_into, decode_into_with_options,
+    DecodeSink,
 };
+#[cfg(feature = "zeroize")]
+pub use decode::streaming::ZeroizingInput;
 pub use decode::streaming::{HexDecodeAsciiIterator, HexDecodeIterator};
-pub use decode::FromHexError;
+pub use decode::validate::{
+    decoded_len, validate, validate_all, validate_all_with_callback, validate_all_with_options,
+    validate_ascii, validate_ascii_with_options, validate_with_options,
+};
+pub use decode::words::{
+    decode_ascii_words, decode_ascii_words_with_options, decode_words, decode_words_with_options,
+};
+pub use decode::{
+    CasePolicy, DecodeOptions, FromHexError, FromHexErrorKind, OddLengthPolicy, PrefixPolicy,
+    Snippet,
+};
+
+#[cfg(feature = "std")]
+pub use decode::vec::{decode, decode_ascii, decode_ascii_with_options, decode_with_options};
+#[cfg(all(feature = "std", feature = "zeroize"))]
+pub use decode::vec::{
+    decode_ascii_zeroizing, decode_ascii_zeroizing_with_options, decode_zeroizing,
+    decode_zeroizing_with_options,
+};
+#[cfg(feature = "std")]
+pub use decode::words::{
+    decode_ascii_words_vec, decode_ascii_words_vec_with_options, decode_words_vec,
+    decode_words_vec_with_options,
+};
 
 #[cfg(feature = "std")]
-pub use decode::vec::{decode, decode_ascii};
+pub use dump::decode_dump;
+pub use dump::{DumpDecodeIterator, FromDumpError, FromDumpErrorKind};
 
-pub use encode::{hex, Case, DisplayOptions, Hex};
+pub use encode::{
+    encode_array, encode_in_place, encoded_len, encoded_str, hex, hex_words, Case, DisplayOptions,
+    Hex, HexWords,
+};
+pub use endian::{Endian, Word};
+pub use line_error::{LineError, LineErrorKind};
+pub use nibbles::{
+    decode_ascii_nibbles, decode_ascii_nibbles_with_options, decode_nibbles,
+    decode_nibbles_with_options, NibbleIter, NibbleOrder, Nibbles,
+};
+
+#[cfg(feature = "std")]
+pub use image::MemoryImage;
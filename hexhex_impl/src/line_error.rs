@@ -0,0 +1,60 @@
+use crate::decode::common::column;
+
+/// Represents an error encountered while reading a line-based format, like a hex dump or an Intel HEX file
+///
+/// Each format has its own kind of error, e.g. [`FromDumpErrorKind`](crate::FromDumpErrorKind),
+/// and an alias for this type, e.g. [`FromDumpError`](crate::FromDumpError).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineError<K> {
+    pub(crate) line: usize,
+    pub(crate) column: usize,
+    pub(crate) kind: K,
+}
+
+/// The kinds of errors of a line-based format, see [`LineError`]
+pub trait LineErrorKind: core::fmt::Display {
+    /// Name of the format, for error messages
+    const FORMAT: &'static str;
+}
+
+impl<K: Copy> LineError<K> {
+    /// The error at the given byte position of `text`, which is the line with 0-based index `line`
+    pub(crate) fn at(line: usize, text: &str, position: usize, kind: K) -> Self {
+        Self {
+            line: line + 1,
+            column: column(text, position),
+            kind,
+        }
+    }
+
+    /// The 1-based line number at which the error occurred
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// The 1-based column (in chars) at which the error occurred
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// What went wrong
+    pub fn kind(&self) -> K {
+        self.kind
+    }
+}
+
+impl<K: LineErrorKind> core::fmt::Display for LineError<K> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(
+            f,
+            "error converting from {}: {} at line {}, column {}",
+            K::FORMAT,
+            self.kind,
+            self.line,
+            self.column
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K: LineErrorKind + core::fmt::Debug> std::error::Error for LineError<K> {}
@@ -0,0 +1,244 @@
+use crate::decode::common::needs_padding;
+use crate::decode::{DecodeOptions, FromHexError, OddLengthPolicy};
+use crate::encode::{digits, Case, DisplayOptions};
+
+/// Which half of each byte comes first in a [`Nibbles`] view
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum NibbleOrder {
+    /// The high nibble (as in hex)
+    #[default]
+    HighFirst,
+    /// The low nibble
+    LowFirst,
+}
+
+/// All nibble values, so that indexing can return references
+const VALUES: [u8; 16] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15];
+
+/// View of a byte slice as a sequence of nibbles (half-bytes), which may have an odd length
+///
+/// Iterable and indexable, and displayed as hex digits like [`Hex`](crate::Hex), one digit per nibble.
+///
+/// # Example
+///
+/// ```
+/// use hexhex_impl::*;
+/// let bytes = [0x12, 0x34];
+/// let nibbles = Nibbles::new(&bytes).with_len(3);
+/// assert_eq!(nibbles[2], 3);
+/// assert!(nibbles.iter().eq([1, 2, 3]));
+/// assert_eq!(nibbles.to_string(), "123");
+///
+/// let nibbles = Nibbles::new(&bytes).with_order(NibbleOrder::LowFirst);
+/// assert_eq!(nibbles.to_string(), "2143");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Nibbles<'a> {
+    bytes: &'a [u8],
+    len: usize,
+    order: NibbleOrder,
+    options: DisplayOptions,
+}
+
+impl<'a> Nibbles<'a> {
+    /// View all nibbles of `bytes`, high nibble first.
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self {
+            bytes,
+            len: 2 * bytes.len(),
+            order: NibbleOrder::default(),
+            options: DisplayOptions::default(),
+        }
+    }
+
+    /// Only view the first `len` nibbles (at most all of them), which leaves out the last one if `len` is odd.
+    pub fn with_len(mut self, len: usize) -> Self {
+        self.len = len.min(2 * self.bytes.len());
+        self
+    }
+
+    /// Use the given order of nibbles within each byte
+    pub fn with_order(mut self, order: NibbleOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Use the given display options
+    pub fn with_options(mut self, options: DisplayOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Display with or without prefix
+    pub fn with_prefix(mut self, with_prefix: bool) -> Self {
+        self.options.with_prefix = with_prefix;
+        self
+    }
+
+    /// Display in lower or upper case
+    pub fn with_case(mut self, case: Case) -> Self {
+        self.options.case = case;
+        self
+    }
+
+    /// The number of nibbles
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether there are no nibbles
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The underlying bytes (including the unused half of the last one, if the length is odd)
+    pub fn bytes(&self) -> &'a [u8] {
+        &self.bytes[..self.len.div_ceil(2)]
+    }
+
+    /// The nibble at `index`, if it is in range
+    pub fn get(&self, index: usize) -> Option<u8> {
+        if index >= self.len {
+            return None;
+        }
+        let byte = self.bytes[index / 2];
+        let high = (index % 2 == 1) == (self.order == NibbleOrder::LowFirst);
+        Some(if high { byte >> 4 } else { byte & 0xf })
+    }
+
+    /// Iterate over the nibbles
+    pub fn iter(&self) -> NibbleIter<'a> {
+        NibbleIter {
+            nibbles: *self,
+            range: 0..self.len,
+        }
+    }
+}
+
+impl core::ops::Index<usize> for Nibbles<'_> {
+    type Output = u8;
+
+    /// Panics if `index` is out of range, like slices do.
+    fn index(&self, index: usize) -> &u8 {
+        match self.get(index) {
+            Some(nibble) => &VALUES[nibble as usize],
+            None => panic!(
+                "index out of bounds: the len is {} but the index is {index}",
+                self.len
+            ),
+        }
+    }
+}
+
+impl<'a> IntoIterator for Nibbles<'a> {
+    type Item = u8;
+    type IntoIter = NibbleIter<'a>;
+
+    fn into_iter(self) -> NibbleIter<'a> {
+        self.iter()
+    }
+}
+
+impl core::fmt::Display for Nibbles<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        if self.options.with_prefix {
+            f.write_str("0x")?;
+        }
+        let digits = digits(self.options.case);
+        self.iter()
+            .try_for_each(|nibble| core::fmt::Write::write_char(f, digits[nibble as usize] as char))
+    }
+}
+
+/// Iterator over the nibbles of a [`Nibbles`] view
+#[derive(Debug, Clone)]
+pub struct NibbleIter<'a> {
+    nibbles: Nibbles<'a>,
+    range: core::ops::Range<usize>,
+}
+
+impl Iterator for NibbleIter<'_> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        self.range.next().and_then(|i| self.nibbles.get(i))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for NibbleIter<'_> {
+    fn next_back(&mut self) -> Option<u8> {
+        self.range.next_back().and_then(|i| self.nibbles.get(i))
+    }
+}
+
+impl ExactSizeIterator for NibbleIter<'_> {}
+
+impl core::iter::FusedIterator for NibbleIter<'_> {}
+
+/// Decode a hex string with any number of digits into `dst`, one nibble per digit, high nibble first.
+///
+/// Unlike [`decode_to_buf`](crate::decode_to_buf), an odd number of digits is fine:
+/// the last digit then goes into the high nibble of the last byte, whose low nibble is zero.
+/// Otherwise, this accepts the same input and reports the same errors.
+///
+/// # Example
+///
+/// ```
+/// use hexhex_impl::*;
+/// let mut buf = [0u8; 4];
+/// let nibbles = decode_nibbles("0x12345", &mut buf).unwrap();
+/// assert_eq!(nibbles.len(), 5);
+/// assert_eq!(nibbles.bytes(), [0x12, 0x34, 0x50]);
+/// assert_eq!(nibbles.to_string(), "12345");
+/// ```
+pub fn decode_nibbles<'a>(hex: &str, dst: &'a mut [u8]) -> Result<Nibbles<'a>, FromHexError> {
+    decode_nibbles_with_options(hex, dst, DecodeOptions::default())
+}
+
+/// Like [`decode_nibbles`], but with the given options. The odd length policy is ignored.
+pub fn decode_nibbles_with_options<'a>(
+    hex: &str,
+    dst: &'a mut [u8],
+    options: DecodeOptions,
+) -> Result<Nibbles<'a>, FromHexError> {
+    let options = options.with_odd_length(OddLengthPolicy::PadLeft);
+    let len = crate::decode_to_buf_with_options(hex, dst, options)?;
+    let odd = needs_padding(hex.chars(), &options);
+    Ok(shift_padding(dst, len, odd))
+}
+
+/// Like [`decode_nibbles`], but for a byte string.
+pub fn decode_ascii_nibbles<'a>(
+    hex: &[u8],
+    dst: &'a mut [u8],
+) -> Result<Nibbles<'a>, FromHexError> {
+    decode_ascii_nibbles_with_options(hex, dst, DecodeOptions::default())
+}
+
+/// Like [`decode_ascii_nibbles`], but with the given options. The odd length policy is ignored.
+pub fn decode_ascii_nibbles_with_options<'a>(
+    hex: &[u8],
+    dst: &'a mut [u8],
+    options: DecodeOptions,
+) -> Result<Nibbles<'a>, FromHexError> {
+    let options = options.with_odd_length(OddLengthPolicy::PadLeft);
+    let len = crate::decode_ascii_to_buf_with_options(hex, dst, options)?;
+    let odd = needs_padding(hex.iter().map(|&b| b as char), &options);
+    Ok(shift_padding(dst, len, odd))
+}
+
+/// Move the padding of an odd number of digits, decoded with a leading zero, to the end
+fn shift_padding(dst: &mut [u8], len: usize, odd: bool) -> Nibbles<'_> {
+    let bytes = &mut dst[..len];
+    if odd {
+        for i in 0..len {
+            let next = bytes.get(i + 1).map_or(0, |b| b >> 4);
+            bytes[i] = bytes[i] << 4 | next;
+        }
+    }
+    Nibbles::new(bytes).with_len(2 * len - usize::from(odd))
+}
@@ -0,0 +1,486 @@
+//! Reading and writing Verilog `$readmemh` memory files
+//!
+//! A memory file consists of hex words separated by whitespace, which are loaded into consecutive
+//! addresses. An `@address` directive (in hex, counting words) sets the address of the next word.
+//! Comments start with `//` and extend to the end of the line, or are enclosed in `/*` and `*/`.
+//! Underscores within words and addresses are ignored, except at the start.
+//!
+//! Words are 1 to 64 bits wide. The unknown and high-impedance digits `x` and `z` are not supported.
+//!
+//! Parsing works without allocating via [`WordIterator`], and [`write_words`] writes words
+//! from a slice. With the `std` feature, `read_words` and `read_image` collect a whole file
+//! into a word array or byte image, and `write_image` writes a byte image back out.
+//!
+//! # Example
+//!
+//! ```
+//! # #[cfg(feature = "std")]
+//! # {
+//! use hexhex_impl::readmemh::{self, ReadOptions};
+//! use hexhex_impl::Endian;
+//! let file = "\
+//! // boot ROM
+//! @10
+//! dead beef /* magic */
+//! 0001
+//! ";
+//! let options = ReadOptions {
+//!     word_width: 16,
+//!     endian: Endian::Little,
+//!     ..Default::default()
+//! };
+//! let words = readmemh::read_words(file, options).unwrap();
+//! assert_eq!(words.len(), 0x13);
+//! assert_eq!(&words[0x10..], &[0xdead, 0xbeef, 0x0001]);
+//! let image = readmemh::read_image(file, options).unwrap();
+//! assert_eq!(image.get(0x20), Some(0xad));
+//! assert_eq!(image.get(0x21), Some(0xde));
+//! # }
+//! ```
+
+use fallible_iterator::FallibleIterator;
+
+use crate::decode::FromHexErrorKind;
+use crate::line_error::{LineError, LineErrorKind};
+use crate::Endian;
+
+#[cfg(feature = "std")]
+use crate::MemoryImage;
+
+/// Represents an error encountered while reading a memory file
+pub type FromMemError = LineError<FromMemErrorKind>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FromMemErrorKind {
+    /// A word or address contains a character that is not a hex digit.
+    Hex(FromHexErrorKind),
+    /// An `@` is not followed by an address.
+    InvalidAddress,
+    /// A word does not fit into the word width.
+    WordTooWide,
+    /// A `/*` comment is not closed.
+    UnterminatedComment,
+    /// An address does not fit into 32 bits, or a word would be placed past the end of the address space.
+    AddressOverflow,
+    /// A word would be placed at or past [`ReadOptions::max_words`] in a word array.
+    TooManyWords,
+}
+
+impl core::fmt::Display for FromMemErrorKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            FromMemErrorKind::Hex(kind) => write!(f, "{kind}"),
+            FromMemErrorKind::InvalidAddress => write!(f, "invalid address"),
+            FromMemErrorKind::WordTooWide => write!(f, "word too wide"),
+            FromMemErrorKind::UnterminatedComment => write!(f, "unterminated comment"),
+            FromMemErrorKind::AddressOverflow => write!(f, "address overflow"),
+            FromMemErrorKind::TooManyWords => write!(f, "too many words"),
+        }
+    }
+}
+
+impl LineErrorKind for FromMemErrorKind {
+    const FORMAT: &'static str = "memory file";
+}
+
+/// Options for reading memory files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ReadOptions {
+    /// Width of a word in bits (1 to 64; other values are clamped).
+    pub word_width: u8,
+    /// Byte order of the words in a byte image
+    pub endian: Endian,
+    /// Maximum number of words in the array returned by `read_words`, which limits the highest address
+    /// (1 Mi words by default). Byte images are sparse and not limited.
+    pub max_words: usize,
+}
+
+impl Default for ReadOptions {
+    fn default() -> Self {
+        Self {
+            word_width: 8,
+            endian: Endian::Big,
+            max_words: 1 << 20,
+        }
+    }
+}
+
+fn clamp_width(word_width: u8) -> u32 {
+    u32::from(word_width.clamp(1, 64))
+}
+
+/// Number of bytes needed for a word of the given (clamped) width
+#[cfg(feature = "std")]
+fn word_bytes(width: u32) -> usize {
+    width.div_ceil(8) as usize
+}
+
+/// Parse hex digits with optional underscores into a number of at most `width` bits.
+fn parse_hex(
+    line: usize,
+    text: &str,
+    start: usize,
+    width: u32,
+    too_wide: FromMemErrorKind,
+) -> Result<u64, FromMemError> {
+    let digits = &text[start..];
+    let digits = &digits[..digits
+        .find(|c: char| c.is_whitespace() || c == '/')
+        .unwrap_or(digits.len())];
+    let mut value = 0u64;
+    for (i, c) in digits.char_indices() {
+        if c == '_' && i > 0 {
+            continue;
+        }
+        let Some(digit) = c.to_digit(16) else {
+            let kind = FromMemErrorKind::Hex(FromHexErrorKind::UnexpectedCharacter(c));
+            return Err(FromMemError::at(line, text, start + i, kind));
+        };
+        if value >> 60 != 0 {
+            return Err(FromMemError::at(line, text, start, too_wide));
+        }
+        value = value << 4 | u64::from(digit);
+    }
+    if width < 64 && value >> width != 0 {
+        return Err(FromMemError::at(line, text, start, too_wide));
+    }
+    Ok(value)
+}
+
+/// Fallible iterator over the words of a memory file, with word addresses.
+///
+/// Does not allocate.
+///
+/// # Example
+///
+/// ```
+/// use fallible_iterator::FallibleIterator;
+/// use hexhex_impl::readmemh::{ReadOptions, WordIterator};
+/// let options = ReadOptions {
+///     word_width: 12,
+///     ..Default::default()
+/// };
+/// let mut words = WordIterator::new("fff // comment\n@1_0 abc", options);
+/// assert_eq!(words.next().unwrap(), Some((0x00, 0xfff)));
+/// assert_eq!(words.next().unwrap(), Some((0x10, 0xabc)));
+/// assert_eq!(words.next().unwrap(), None);
+/// ```
+pub struct WordIterator<'a> {
+    lines: core::iter::Enumerate<core::str::Lines<'a>>,
+    /// line being read: 0-based index, text, and byte position of the rest
+    current: Option<(usize, &'a str, usize)>,
+    /// start of an open `/*` comment
+    comment: Option<(usize, &'a str, usize)>,
+    /// start of the last word
+    word: (usize, &'a str, usize),
+    /// address of the next word, or `None` past the end of the address space
+    address: Option<u32>,
+    width: u32,
+}
+
+impl<'a> WordIterator<'a> {
+    /// Construct a new WordIterator from the text of a memory file.
+    pub fn new(file: &'a str, options: ReadOptions) -> Self {
+        Self {
+            lines: file.lines().enumerate(),
+            current: None,
+            comment: None,
+            word: (0, "", 0),
+            address: Some(0),
+            width: clamp_width(options.word_width),
+        }
+    }
+
+    /// An error at the last word
+    fn error(&self, kind: FromMemErrorKind) -> FromMemError {
+        let (line, text, position) = self.word;
+        FromMemError::at(line, text, position, kind)
+    }
+}
+
+impl FallibleIterator for WordIterator<'_> {
+    type Item = (u32, u64);
+    type Error = FromMemError;
+
+    fn next(&mut self) -> Result<Option<(u32, u64)>, FromMemError> {
+        loop {
+            let Some((line, text, position)) = self.current else {
+                match self.lines.next() {
+                    Some((line, text)) => self.current = Some((line, text, 0)),
+                    None => {
+                        return match self.comment {
+                            Some((line, text, position)) => Err(FromMemError::at(
+                                line,
+                                text,
+                                position,
+                                FromMemErrorKind::UnterminatedComment,
+                            )),
+                            None => Ok(None),
+                        }
+                    }
+                }
+                continue;
+            };
+            let rest = &text[position..];
+            if self.comment.is_some() {
+                self.current = rest.find("*/").map(|end| {
+                    self.comment = None;
+                    (line, text, position + end + 2)
+                });
+                continue;
+            }
+            let token = rest.trim_start();
+            let start = text.len() - token.len();
+            if token.is_empty() || token.starts_with("//") {
+                self.current = None;
+                continue;
+            }
+            if token.starts_with("/*") {
+                self.comment = Some((line, text, start));
+                self.current = Some((line, text, start + 2));
+                continue;
+            }
+            let len = token
+                .find(|c: char| c.is_whitespace() || c == '/')
+                .unwrap_or(token.len());
+            if len == 0 {
+                let kind = FromMemErrorKind::Hex(FromHexErrorKind::UnexpectedCharacter('/'));
+                return Err(FromMemError::at(line, text, start, kind));
+            }
+            self.current = Some((line, text, start + len));
+            if token.starts_with('@') {
+                if len == 1 {
+                    return Err(FromMemError::at(
+                        line,
+                        text,
+                        start,
+                        FromMemErrorKind::InvalidAddress,
+                    ));
+                }
+                let address =
+                    parse_hex(line, text, start + 1, 32, FromMemErrorKind::AddressOverflow)?;
+                self.address = Some(address as u32);
+            } else {
+                self.word = (line, text, start);
+                let word = parse_hex(line, text, start, self.width, FromMemErrorKind::WordTooWide)?;
+                let address = self
+                    .address
+                    .ok_or_else(|| self.error(FromMemErrorKind::AddressOverflow))?;
+                self.address = address.checked_add(1);
+                return Ok(Some((address, word)));
+            }
+        }
+    }
+}
+
+/// Read a memory file into an array of words, indexed by word address.
+///
+/// The array ends at the highest address with a word; addresses without a word are zero.
+/// Later words overwrite earlier ones at the same address.
+/// Words at or past [`ReadOptions::max_words`] are an error, since the array would be too large.
+#[cfg(feature = "std")]
+pub fn read_words(file: &str, options: ReadOptions) -> Result<std::vec::Vec<u64>, FromMemError> {
+    let mut words = std::vec::Vec::new();
+    let mut iter = WordIterator::new(file, options);
+    while let Some((address, word)) = iter.next()? {
+        let index = match usize::try_from(address) {
+            Ok(index) if index < options.max_words => index,
+            _ => return Err(iter.error(FromMemErrorKind::TooManyWords)),
+        };
+        if words.len() <= index {
+            words.resize(index + 1, 0);
+        }
+        words[index] = word;
+    }
+    Ok(words)
+}
+
+/// Read a memory file into a byte image.
+///
+/// Each word takes up as many bytes as needed for the word width, in the configured byte order,
+/// at the word address times the number of bytes per word.
+/// Later words overwrite earlier ones at the same address.
+#[cfg(feature = "std")]
+pub fn read_image(file: &str, options: ReadOptions) -> Result<MemoryImage, FromMemError> {
+    let bytes_per_word = word_bytes(clamp_width(options.word_width));
+    let mut image = MemoryImage::new();
+    // contiguous bytes not yet inserted into the image
+    let mut run_start = 0u32;
+    let mut run = std::vec::Vec::new();
+    let mut iter = WordIterator::new(file, options);
+    while let Some((address, word)) = iter.next()? {
+        let start = u64::from(address) * bytes_per_word as u64;
+        if start + bytes_per_word as u64 > 1 << 32 {
+            return Err(iter.error(FromMemErrorKind::AddressOverflow));
+        }
+        if start != u64::from(run_start) + run.len() as u64 {
+            image.insert(run_start, &run);
+            run.clear();
+            run_start = start as u32;
+        }
+        let bytes = options.endian.word_to_bytes(word, bytes_per_word);
+        run.extend_from_slice(&bytes[..bytes_per_word]);
+    }
+    image.insert(run_start, &run);
+    Ok(image)
+}
+
+/// Options for writing memory files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WriteOptions {
+    /// Width of a word in bits (1 to 64; other values are clamped).
+    pub word_width: u8,
+    /// Byte order of the words in a byte image
+    pub endian: Endian,
+    /// Maximum number of words per line (0 is treated as 1).
+    pub words_per_line: u8,
+}
+
+impl Default for WriteOptions {
+    fn default() -> Self {
+        Self {
+            word_width: 8,
+            endian: Endian::Big,
+            words_per_line: 16,
+        }
+    }
+}
+
+/// Writes words as lines of zero-padded lowercase hex, with `@address` directives for gaps.
+struct WordWriter<'w, W> {
+    out: &'w mut W,
+    width: u32,
+    digits: usize,
+    words_per_line: usize,
+    /// number of words on the current line
+    column: usize,
+    /// address of the next word if no directive is written
+    next: u64,
+}
+
+impl<'w, W: core::fmt::Write> WordWriter<'w, W> {
+    fn new(out: &'w mut W, options: WriteOptions) -> Self {
+        let width = clamp_width(options.word_width);
+        Self {
+            out,
+            width,
+            digits: width.div_ceil(4) as usize,
+            words_per_line: usize::from(options.words_per_line.max(1)),
+            column: 0,
+            next: 0,
+        }
+    }
+
+    fn word(&mut self, address: u64, word: u64) -> core::fmt::Result {
+        if self.width < 64 && word >> self.width != 0 {
+            return Err(core::fmt::Error);
+        }
+        if address != self.next {
+            self.end_line()?;
+            writeln!(self.out, "@{address:x}")?;
+        } else if self.column == self.words_per_line {
+            self.end_line()?;
+        }
+        if self.column > 0 {
+            write!(self.out, " ")?;
+        }
+        write!(self.out, "{word:0width$x}", width = self.digits)?;
+        self.column += 1;
+        self.next = address + 1;
+        Ok(())
+    }
+
+    fn end_line(&mut self) -> core::fmt::Result {
+        if self.column > 0 {
+            self.column = 0;
+            writeln!(self.out)?;
+        }
+        Ok(())
+    }
+}
+
+/// Write words at consecutive addresses, starting at 0.
+///
+/// Fails if a word does not fit into the word width.
+///
+/// # Example
+///
+/// ```
+/// use hexhex_impl::readmemh::{write_words, WriteOptions};
+/// let options = WriteOptions {
+///     word_width: 12,
+///     words_per_line: 2,
+///     ..Default::default()
+/// };
+/// let mut out = String::new();
+/// write_words(&mut out, &[0x123, 0x4, 0xfff], options).unwrap();
+/// assert_eq!(out, "123 004\nfff\n");
+/// ```
+pub fn write_words<W: core::fmt::Write>(
+    out: &mut W,
+    words: &[u64],
+    options: WriteOptions,
+) -> core::fmt::Result {
+    let mut writer = WordWriter::new(out, options);
+    for (address, &word) in words.iter().enumerate() {
+        writer.word(address as u64, word)?;
+    }
+    writer.end_line()
+}
+
+/// Write a byte image as words, with `@address` directives for gaps.
+///
+/// Each word is made up of as many bytes as needed for the word width, in the configured byte order.
+/// Bytes missing from a partially filled word are zero.
+/// Fails if a word does not fit into the word width.
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(feature = "std")]
+/// # {
+/// use hexhex_impl::readmemh::{write_image, WriteOptions};
+/// use hexhex_impl::{Endian, MemoryImage};
+/// let mut image = MemoryImage::new();
+/// image.insert(0x0, &[0x01, 0x02, 0x03, 0x04]);
+/// image.insert(0x10, &[0x05]);
+/// let options = WriteOptions {
+///     word_width: 16,
+///     endian: Endian::Little,
+///     ..Default::default()
+/// };
+/// let mut out = String::new();
+/// write_image(&mut out, &image, options).unwrap();
+/// assert_eq!(out, "0201 0403\n@8\n0005\n");
+/// # }
+/// ```
+#[cfg(feature = "std")]
+pub fn write_image<W: core::fmt::Write>(
+    out: &mut W,
+    image: &MemoryImage,
+    options: WriteOptions,
+) -> core::fmt::Result {
+    let bytes_per_word = word_bytes(clamp_width(options.word_width));
+    let mut writer = WordWriter::new(out, options);
+    // first word address not yet written, as consecutive segments may share a word
+    let mut next = 0u64;
+    for (start, bytes) in image.segments() {
+        let start = u64::from(start);
+        let end = start + bytes.len() as u64;
+        let first = (start / bytes_per_word as u64).max(next);
+        next = end.div_ceil(bytes_per_word as u64);
+        for address in first..next {
+            let mut word = [0u8; 8];
+            for (i, byte) in word[..bytes_per_word].iter_mut().enumerate() {
+                let byte_address = address * bytes_per_word as u64 + i as u64;
+                *byte = u32::try_from(byte_address)
+                    .ok()
+                    .and_then(|a| image.get(a))
+                    .unwrap_or(0);
+            }
+            let word = options.endian.bytes_to_word(&word[..bytes_per_word]);
+            writer.word(address, word)?;
+        }
+    }
+    writer.end_line()
+}
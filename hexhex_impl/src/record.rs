@@ -0,0 +1,46 @@
+/// The data of a single record (at most 255 bytes)
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RecordData {
+    len: u8,
+    bytes: [u8; 255],
+}
+
+impl RecordData {
+    /// Copy up to 255 bytes into a new RecordData.
+    /// Returns `None` if there are more than 255 bytes.
+    pub fn new(data: &[u8]) -> Option<Self> {
+        let len = u8::try_from(data.len()).ok()?;
+        let mut bytes = [0u8; 255];
+        bytes[..data.len()].copy_from_slice(data);
+        Some(Self { len, bytes })
+    }
+
+    /// Split into the bytes before `mid` and the bytes from `mid` on
+    pub(crate) fn split_at(&self, mid: usize) -> (Self, Self) {
+        let (head, tail) = (**self).split_at(mid);
+        // both halves are shorter than `self`
+        let copy = |data: &[u8]| {
+            let mut bytes = [0u8; 255];
+            bytes[..data.len()].copy_from_slice(data);
+            Self {
+                len: data.len() as u8,
+                bytes,
+            }
+        };
+        (copy(head), copy(tail))
+    }
+}
+
+impl core::ops::Deref for RecordData {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.bytes[..self.len as usize]
+    }
+}
+
+impl core::fmt::Debug for RecordData {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
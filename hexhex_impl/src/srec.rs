@@ -0,0 +1,579 @@
+//! Reading and writing Motorola S-record (S19, S28, S37) files
+//!
+//! An S-record file consists of records, one per line, each of which looks like `STLLAA..DD..CC`:
+//! the letter `S`, a record type digit `T`, a byte count `LL`, a 16-, 24-, or 32-bit address `AA..`,
+//! the data `DD..`, and a checksum `CC`, all in hex.
+//!
+//! | Type | Address | Contents |
+//! |------|---------|----------|
+//! | S0   | 16 bit  | header |
+//! | S1   | 16 bit  | data |
+//! | S2   | 24 bit  | data |
+//! | S3   | 32 bit  | data |
+//! | S5   | 16 bit  | number of preceding data records |
+//! | S6   | 24 bit  | number of preceding data records |
+//! | S7   | 32 bit  | start address |
+//! | S8   | 24 bit  | start address |
+//! | S9   | 16 bit  | start address |
+//!
+//! Parsing works without allocating via [`RecordIterator`] and [`DataIterator`].
+//! With the `std` feature, `read` collects a whole file into an `SRecordFile` image,
+//! which can be written back out with `SRecordFile::write`.
+//!
+//! # Example
+//!
+//! ```
+//! # #[cfg(feature = "std")]
+//! # {
+//! use hexhex_impl::srec;
+//! let file = "\
+//! S00600004844521B
+//! S107000001020304EE
+//! S5030001FB
+//! S9030000FC
+//! ";
+//! let srec = srec::read(file).unwrap();
+//! assert_eq!(srec.header, b"HDR");
+//! assert_eq!(srec.image.get(2), Some(0x03));
+//! # }
+//! ```
+
+use fallible_iterator::FallibleIterator;
+
+use crate::decode::{DecodeOptions, FromHexErrorKind, PrefixPolicy};
+use crate::line_error::{LineError, LineErrorKind};
+use crate::{decode_to_buf_with_options, Case, Hex};
+
+#[cfg(feature = "std")]
+use crate::MemoryImage;
+
+pub use crate::record::RecordData;
+
+/// Represents an error encountered while reading an S-record file
+pub type FromSrecError = LineError<FromSrecErrorKind>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FromSrecErrorKind {
+    /// The hex digits of a record are malformed.
+    Hex(FromHexErrorKind),
+    /// A record does not start with `S`.
+    MissingStartCode,
+    /// The record type is not one of S0-S3 or S5-S9.
+    UnsupportedRecordType(char),
+    /// The number of bytes in a record does not match its byte count, or is too short for its address.
+    InvalidLength,
+    /// The checksum of a record is wrong.
+    ChecksumMismatch { expected: u8, found: u8 },
+    /// A count or start address record contains data.
+    InvalidRecord,
+    /// A count record does not match the number of preceding data records.
+    CountMismatch { expected: u32, found: u32 },
+    /// The data of a record extends past the 32-bit address space.
+    AddressOverflow,
+}
+
+impl core::fmt::Display for FromSrecErrorKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            FromSrecErrorKind::Hex(kind) => write!(f, "{kind}"),
+            FromSrecErrorKind::MissingStartCode => write!(f, "record does not start with 'S'"),
+            FromSrecErrorKind::UnsupportedRecordType(t) => {
+                write!(f, "unsupported record type {t}")
+            }
+            FromSrecErrorKind::InvalidLength => write!(f, "record length does not match"),
+            FromSrecErrorKind::ChecksumMismatch { expected, found } => {
+                write!(f, "expected checksum 0x{expected:02x}, found 0x{found:02x}")
+            }
+            FromSrecErrorKind::InvalidRecord => write!(f, "invalid record"),
+            FromSrecErrorKind::CountMismatch { expected, found } => {
+                write!(f, "expected record count {expected}, found {found}")
+            }
+            FromSrecErrorKind::AddressOverflow => write!(f, "address overflow"),
+        }
+    }
+}
+
+impl LineErrorKind for FromSrecErrorKind {
+    const FORMAT: &'static str = "S-record";
+}
+
+/// Size of the address field of a record
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum AddressWidth {
+    /// 16-bit addresses (S1 data, S9 start address)
+    Bits16,
+    /// 24-bit addresses (S2 data, S8 start address)
+    Bits24,
+    /// 32-bit addresses (S3 data, S7 start address)
+    Bits32,
+}
+
+impl AddressWidth {
+    fn bytes(self) -> usize {
+        match self {
+            AddressWidth::Bits16 => 2,
+            AddressWidth::Bits24 => 3,
+            AddressWidth::Bits32 => 4,
+        }
+    }
+
+    /// The smallest width that can hold the given address
+    #[cfg(feature = "std")]
+    fn fitting(address: u64) -> Option<Self> {
+        match address {
+            0..=0xFFFF => Some(AddressWidth::Bits16),
+            0x1_0000..=0xFF_FFFF => Some(AddressWidth::Bits24),
+            0x100_0000..=0xFFFF_FFFF => Some(AddressWidth::Bits32),
+            _ => None,
+        }
+    }
+}
+
+/// A single S-record
+///
+/// Displays as the record's line (without line terminator).
+///
+/// # Example
+///
+/// ```
+/// use hexhex_impl::srec::{parse_record, AddressWidth, Record};
+/// let record = parse_record("S80401234592").unwrap();
+/// assert_eq!(
+///     record,
+///     Record::StartAddress {
+///         address: 0x012345,
+///         width: AddressWidth::Bits24
+///     }
+/// );
+/// assert_eq!(record.to_string(), "S80401234592");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[allow(clippy::large_enum_variant)] // data records are the common case, and this avoids allocating
+pub enum Record {
+    /// S0: header, usually a file or module name
+    Header(RecordData),
+    /// S1, S2, S3: data at an address of the given width
+    ///
+    /// Fails to display if the address does not fit into the width.
+    Data {
+        address: u32,
+        width: AddressWidth,
+        data: RecordData,
+    },
+    /// S5, S6: number of preceding data records
+    ///
+    /// Displays as S5 if the count fits into 16 bits, and S6 otherwise,
+    /// and fails to display if it does not fit into 24 bits.
+    Count(u32),
+    /// S7, S8, S9: start address of the given width
+    ///
+    /// Fails to display if the address does not fit into the width.
+    StartAddress { address: u32, width: AddressWidth },
+}
+
+impl core::fmt::Display for Record {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        let (kind, address, width, data): (u8, u32, AddressWidth, &[u8]) = match self {
+            Record::Header(data) => (0, 0, AddressWidth::Bits16, data),
+            Record::Data {
+                address,
+                width,
+                data,
+            } => {
+                let kind = match width {
+                    AddressWidth::Bits16 => 1,
+                    AddressWidth::Bits24 => 2,
+                    AddressWidth::Bits32 => 3,
+                };
+                (kind, *address, *width, data)
+            }
+            Record::Count(count) if *count <= 0xFFFF => (5, *count, AddressWidth::Bits16, &[]),
+            Record::Count(count) => (6, *count, AddressWidth::Bits24, &[]),
+            Record::StartAddress { address, width } => {
+                let kind = match width {
+                    AddressWidth::Bits16 => 9,
+                    AddressWidth::Bits24 => 8,
+                    AddressWidth::Bits32 => 7,
+                };
+                (kind, *address, *width, &[])
+            }
+        };
+        let address = address.to_be_bytes();
+        let (high, address_bytes) = address.split_at(4 - width.bytes());
+        if high.iter().any(|&b| b != 0) {
+            // the address or count does not fit into the record
+            return Err(core::fmt::Error);
+        }
+        let count =
+            u8::try_from(address_bytes.len() + data.len() + 1).map_err(|_| core::fmt::Error)?;
+        let sum = address_bytes
+            .iter()
+            .chain(data)
+            .fold(count, |sum, b| sum.wrapping_add(*b));
+        write!(
+            f,
+            "S{kind}{count:02X}{}{}{:02X}",
+            Hex::new(address_bytes).with_case(Case::Upper),
+            Hex::new(data).with_case(Case::Upper),
+            !sum
+        )
+    }
+}
+
+fn parse_record_at(line: usize, text: &str) -> Result<Record, FromSrecError> {
+    let content = text.trim_end();
+    let Some(rest) = content.strip_prefix('S') else {
+        return Err(FromSrecError::at(
+            line,
+            text,
+            0,
+            FromSrecErrorKind::MissingStartCode,
+        ));
+    };
+    let mut chars = rest.chars();
+    let kind = chars.next().unwrap_or('\0');
+    let width = match kind {
+        '0' | '1' | '5' | '9' => AddressWidth::Bits16,
+        '2' | '6' | '8' => AddressWidth::Bits24,
+        '3' | '7' => AddressWidth::Bits32,
+        _ => {
+            return Err(FromSrecError::at(
+                line,
+                text,
+                1,
+                FromSrecErrorKind::UnsupportedRecordType(kind),
+            ))
+        }
+    };
+    let mut buf = [0u8; 1 + 255];
+    let options = DecodeOptions::new().with_prefix(PrefixPolicy::Forbidden);
+    let len = decode_to_buf_with_options(chars.as_str(), &mut buf, options).map_err(|e| {
+        FromSrecError::at(line, text, 2 + e.position, FromSrecErrorKind::Hex(e.kind))
+    })?;
+    if len < 2 + width.bytes() || len != 1 + buf[0] as usize {
+        return Err(FromSrecError::at(
+            line,
+            text,
+            2,
+            FromSrecErrorKind::InvalidLength,
+        ));
+    }
+    let sum = buf[..len - 1]
+        .iter()
+        .fold(0u8, |sum, b| sum.wrapping_add(*b));
+    let found = buf[len - 1];
+    if !sum != found {
+        return Err(FromSrecError::at(
+            line,
+            text,
+            2 + 2 * (len - 1),
+            FromSrecErrorKind::ChecksumMismatch {
+                expected: !sum,
+                found,
+            },
+        ));
+    }
+    let (address_bytes, data) = buf[1..len - 1].split_at(width.bytes());
+    let address = address_bytes
+        .iter()
+        .fold(0u32, |address, b| address << 8 | u32::from(*b));
+    let data_position = 2 + 2 * (1 + width.bytes());
+    let record_data = || {
+        RecordData::new(data)
+            .ok_or_else(|| FromSrecError::at(line, text, 2, FromSrecErrorKind::InvalidLength))
+    };
+    let no_data = || {
+        if data.is_empty() {
+            Ok(())
+        } else {
+            Err(FromSrecError::at(
+                line,
+                text,
+                data_position,
+                FromSrecErrorKind::InvalidRecord,
+            ))
+        }
+    };
+    let record = match kind {
+        '0' => Record::Header(record_data()?),
+        '1' | '2' | '3' => {
+            if u64::from(address) + data.len() as u64 > 1 << 32 {
+                return Err(FromSrecError::at(
+                    line,
+                    text,
+                    4,
+                    FromSrecErrorKind::AddressOverflow,
+                ));
+            }
+            Record::Data {
+                address,
+                width,
+                data: record_data()?,
+            }
+        }
+        '5' | '6' => {
+            no_data()?;
+            Record::Count(address)
+        }
+        _ => {
+            no_data()?;
+            Record::StartAddress { address, width }
+        }
+    };
+    Ok(record)
+}
+
+/// Parse a single S-record.
+///
+/// Errors are reported on line 1.
+pub fn parse_record(line: &str) -> Result<Record, FromSrecError> {
+    parse_record_at(0, line)
+}
+
+/// Fallible iterator over the records of an S-record file.
+///
+/// Blank lines are skipped.
+///
+/// Does not allocate.
+pub struct RecordIterator<'a> {
+    lines: core::iter::Enumerate<core::str::Lines<'a>>,
+    /// 0-based index of the last line read
+    line: usize,
+}
+
+impl<'a> RecordIterator<'a> {
+    /// Construct a new RecordIterator from the text of an S-record file.
+    pub fn new(file: &'a str) -> Self {
+        Self {
+            lines: file.lines().enumerate(),
+            line: 0,
+        }
+    }
+}
+
+impl FallibleIterator for RecordIterator<'_> {
+    type Item = Record;
+    type Error = FromSrecError;
+
+    fn next(&mut self) -> Result<Option<Record>, FromSrecError> {
+        for (line, text) in self.lines.by_ref() {
+            self.line = line;
+            if !text.trim().is_empty() {
+                return parse_record_at(line, text).map(Some);
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Fallible iterator over the data of an S-record file.
+///
+/// Checks count records against the number of data records, and records the header and start address.
+///
+/// Does not allocate.
+///
+/// # Example
+///
+/// ```
+/// use fallible_iterator::FallibleIterator;
+/// use hexhex_impl::srec::DataIterator;
+/// let file = "\
+/// S3090800001001020304D4
+/// S70508000101F0
+/// ";
+/// let mut data = DataIterator::new(file);
+/// let (address, bytes) = data.next().unwrap().unwrap();
+/// assert_eq!(address, 0x0800_0010);
+/// assert_eq!(&*bytes, &[1, 2, 3, 4]);
+/// assert!(data.next().unwrap().is_none());
+/// assert_eq!(data.start_address(), Some(0x0800_0101));
+/// ```
+pub struct DataIterator<'a> {
+    records: RecordIterator<'a>,
+    count: u32,
+    header: Option<RecordData>,
+    start_address: Option<u32>,
+}
+
+impl<'a> DataIterator<'a> {
+    /// Construct a new DataIterator from the text of an S-record file.
+    pub fn new(file: &'a str) -> Self {
+        Self {
+            records: RecordIterator::new(file),
+            count: 0,
+            header: None,
+            start_address: None,
+        }
+    }
+
+    /// The header from the records read so far, if any
+    pub fn header(&self) -> Option<RecordData> {
+        self.header
+    }
+
+    /// The start address from the records read so far, if any
+    pub fn start_address(&self) -> Option<u32> {
+        self.start_address
+    }
+}
+
+impl FallibleIterator for DataIterator<'_> {
+    type Item = (u32, RecordData);
+    type Error = FromSrecError;
+
+    fn next(&mut self) -> Result<Option<(u32, RecordData)>, FromSrecError> {
+        loop {
+            match self.records.next()? {
+                None => return Ok(None),
+                Some(Record::Header(data)) => self.header = Some(data),
+                Some(Record::Data { address, data, .. }) => {
+                    self.count = self.count.wrapping_add(1);
+                    return Ok(Some((address, data)));
+                }
+                Some(Record::Count(found)) => {
+                    if found != self.count {
+                        return Err(FromSrecError {
+                            line: self.records.line + 1,
+                            column: 5,
+                            kind: FromSrecErrorKind::CountMismatch {
+                                expected: self.count,
+                                found,
+                            },
+                        });
+                    }
+                }
+                Some(Record::StartAddress { address, .. }) => self.start_address = Some(address),
+            }
+        }
+    }
+}
+
+/// Options for writing S-record files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WriteOptions {
+    /// Address width of the data and start address records,
+    /// or `None` to use the smallest width that fits all addresses.
+    pub address_width: Option<AddressWidth>,
+    /// Maximum number of data bytes per record (1 to 250 for 32-bit addresses;
+    /// larger values are reduced to what fits into a record, and 0 is treated as 1).
+    pub record_length: u8,
+}
+
+impl Default for WriteOptions {
+    fn default() -> Self {
+        Self {
+            address_width: None,
+            record_length: 16,
+        }
+    }
+}
+
+/// The contents of an S-record file
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SRecordFile {
+    /// The data of the S0 header record
+    pub header: std::vec::Vec<u8>,
+    /// The data
+    pub image: MemoryImage,
+    /// The execution start address, if any
+    pub start_address: Option<u32>,
+}
+
+/// Read an S-record file.
+///
+/// Later records overwrite earlier ones at the same address.
+#[cfg(feature = "std")]
+pub fn read(file: &str) -> Result<SRecordFile, FromSrecError> {
+    let mut srec = SRecordFile::default();
+    let mut data = DataIterator::new(file);
+    while let Some((address, bytes)) = data.next()? {
+        srec.image.insert(address, &bytes);
+    }
+    srec.header = data.header().map(|h| h.to_vec()).unwrap_or_default();
+    srec.start_address = data.start_address();
+    Ok(srec)
+}
+
+#[cfg(feature = "std")]
+impl SRecordFile {
+    /// Write the file with the given options.
+    ///
+    /// Writes a header record, the data records, a count record, and a start address record
+    /// (with address 0 if there is no start address).
+    /// Fails if an address does not fit into the configured address width, or the header is too long.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexhex_impl::srec::{AddressWidth, SRecordFile, WriteOptions};
+    /// let mut srec = SRecordFile::default();
+    /// srec.image.insert(0x1000, &[1, 2, 3]);
+    /// let options = WriteOptions {
+    ///     address_width: Some(AddressWidth::Bits24),
+    ///     record_length: 2,
+    /// };
+    /// let mut out = String::new();
+    /// srec.write(&mut out, options).unwrap();
+    /// assert_eq!(out, "\
+    /// S0030000FC
+    /// S2060010000102E6
+    /// S20500100203E5
+    /// S5030002FA
+    /// S804000000FB
+    /// ");
+    /// ```
+    pub fn write<W: core::fmt::Write>(
+        &self,
+        out: &mut W,
+        options: WriteOptions,
+    ) -> core::fmt::Result {
+        let end = self
+            .image
+            .segments()
+            .map(|(address, bytes)| u64::from(address) + bytes.len() as u64 - 1)
+            .chain(self.start_address.map(u64::from))
+            .max()
+            .unwrap_or(0);
+        let width = match options.address_width {
+            Some(width) => width,
+            None => AddressWidth::fitting(end).ok_or(core::fmt::Error)?,
+        };
+        if AddressWidth::fitting(end).ok_or(core::fmt::Error)? > width {
+            return Err(core::fmt::Error);
+        }
+        let header = RecordData::new(&self.header).ok_or(core::fmt::Error)?;
+        writeln!(out, "{}", Record::Header(header))?;
+        let record_length = usize::from(options.record_length).clamp(1, 255 - 1 - width.bytes());
+        let mut count = 0u32;
+        for (address, bytes) in self.image.segments() {
+            for (i, chunk) in bytes.chunks(record_length).enumerate() {
+                let data = RecordData::new(chunk).ok_or(core::fmt::Error)?;
+                let address = address.wrapping_add((i * record_length) as u32);
+                writeln!(
+                    out,
+                    "{}",
+                    Record::Data {
+                        address,
+                        width,
+                        data
+                    }
+                )?;
+                count = count.wrapping_add(1);
+            }
+        }
+        if count <= 0xFF_FFFF {
+            writeln!(out, "{}", Record::Count(count))?;
+        }
+        let address = self.start_address.unwrap_or(0);
+        writeln!(out, "{}", Record::StartAddress { address, width })
+    }
+}
+
+/// Writes the file with the default [`WriteOptions`].
+#[cfg(feature = "std")]
+impl core::fmt::Display for SRecordFile {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        self.write(f, WriteOptions::default())
+    }
+}
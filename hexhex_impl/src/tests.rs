@@ -316,6 +316,194 @@ fn decode_to_buf_exact_double_prefix() {
     assert!(decode_to_buf("0x0x1234", &mut buf).is_err());
 }
 
+#[test]
+fn decode_options_prefix_optional() {
+    let options = DecodeOptions::new().with_prefix(PrefixPolicy::Optional);
+    let mut buf = [0u8; 2];
+    assert!(decode_to_buf_exact_with_options("1234", &mut buf, options).is_ok());
+    assert!(decode_to_buf_exact_with_options("0x1234", &mut buf, options).is_ok());
+    assert_eq!(buf, [0x12, 0x34]);
+    assert!(decode_to_buf_exact_with_options("0x0x1234", &mut buf, options).is_err());
+}
+
+#[test]
+fn decode_options_prefix_required() {
+    let options = DecodeOptions::new().with_prefix(PrefixPolicy::Required);
+    let mut buf = [0u8; 2];
+    assert!(decode_to_buf_exact_with_options("0x1234", &mut buf, options).is_ok());
+    assert_eq!(buf, [0x12, 0x34]);
+    assert!(decode_to_buf_exact_with_options("1234", &mut buf, options).is_err());
+    assert!(decode_to_buf_exact_with_options("0012", &mut buf, options).is_err());
+    assert!(decode_ascii_to_buf_with_options(b"", &mut buf, options).is_err());
+    assert!(decode_ascii_to_buf_with_options(b"0", &mut buf, options).is_err());
+    assert_eq!(
+        decode_ascii_to_buf_with_options(b"0x", &mut buf, options).unwrap(),
+        0
+    );
+}
+
+#[test]
+fn decode_options_prefix_forbidden() {
+    let options = DecodeOptions::new().with_prefix(PrefixPolicy::Forbidden);
+    let mut buf = [0u8; 2];
+    assert!(decode_to_buf_exact_with_options("0012", &mut buf, options).is_ok());
+    assert_eq!(buf, [0x00, 0x12]);
+    assert!(decode_to_buf_exact_with_options("0x12", &mut buf[..1], options).is_err());
+    assert!(decode_ascii_to_buf_exact_with_options(b"0x12", &mut buf[..1], options).is_err());
+}
+
+#[test]
+fn decode_options_case() {
+    let mut buf = [0u8; 2];
+    let lower = DecodeOptions::new().with_case(CasePolicy::Lower);
+    assert!(decode_to_buf_exact_with_options("0xabcd", &mut buf, lower).is_ok());
+    assert!(decode_to_buf_exact_with_options("abCd", &mut buf, lower).is_err());
+    let upper = DecodeOptions::new().with_case(CasePolicy::Upper);
+    assert!(decode_to_buf_exact_with_options("0xABCD", &mut buf, upper).is_ok());
+    assert!(decode_to_buf_exact_with_options("ABcD", &mut buf, upper).is_err());
+    let consistent = DecodeOptions::new().with_case(CasePolicy::Consistent);
+    assert!(decode_ascii_to_buf_exact_with_options(b"abcd", &mut buf, consistent).is_ok());
+    assert!(decode_ascii_to_buf_exact_with_options(b"AB12", &mut buf, consistent).is_ok());
+    assert!(decode_ascii_to_buf_exact_with_options(b"0x12", &mut buf[..1], consistent).is_ok());
+    assert!(decode_ascii_to_buf_exact_with_options(b"aBcd", &mut buf, consistent).is_err());
+    assert!(decode_ascii_to_buf_exact_with_options(b"a1C2", &mut buf, consistent).is_err());
+}
+
+#[test]
+fn decode_options_separators() {
+    let options = DecodeOptions::new().with_separators(": ");
+    let mut buf = [0u8; 3];
+    assert!(decode_to_buf_exact_with_options("c0:ff:ee", &mut buf, options).is_ok());
+    assert_eq!(buf, [0xc0, 0xff, 0xee]);
+    assert!(decode_to_buf_exact_with_options(" c0 ff::ee ", &mut buf, options).is_ok());
+    assert!(decode_to_buf_exact_with_options("0xc0ff:ee", &mut buf, options).is_ok());
+    assert!(decode_to_buf_exact_with_options("c:0ffee", &mut buf, options).is_err());
+    assert!(decode_to_buf_exact_with_options("c0-ff-ee", &mut buf, options).is_err());
+    assert!(decode_to_buf_exact_with_options("c0ffee", &mut buf, DecodeOptions::new()).is_ok());
+    assert!(decode_to_buf_exact_with_options("c0 ffee", &mut buf, DecodeOptions::new()).is_err());
+}
+
+#[test]
+fn decode_options_error_position() {
+    let options = DecodeOptions::new().with_separators(" ");
+    let mut buf = [0u8; 3];
+    let err = decode_to_buf_exact_with_options("c0 fF ée", &mut buf, options).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "error converting from hex: unexpected character é at position 6"
+    );
+    let err = decode_ascii_to_buf_with_options(
+        b"0xAB cd",
+        &mut buf,
+        options.with_case(CasePolicy::Consistent),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "error converting from hex: unexpected byte 0x63 at position 5"
+    );
+}
+
+#[test]
+fn decode_options_pad_left() {
+    let options = DecodeOptions::new().with_odd_length(OddLengthPolicy::PadLeft);
+    let mut buf = [0u8; 2];
+    assert!(decode_to_buf_exact_with_options("fff", &mut buf, options).is_ok());
+    assert_eq!(buf, [0x0f, 0xff]);
+    assert!(decode_ascii_to_buf_exact_with_options(b"0xabc", &mut buf, options).is_ok());
+    assert_eq!(buf, [0x0a, 0xbc]);
+    assert!(decode_ascii_to_buf_exact_with_options(b"0x1234", &mut buf, options).is_ok());
+    assert_eq!(buf, [0x12, 0x34]);
+    assert_eq!(
+        decode_to_buf_with_options("0", &mut buf, options).unwrap(),
+        1
+    );
+    assert_eq!(buf[0], 0x00);
+    assert_eq!(
+        decode_to_buf_with_options("", &mut buf, options).unwrap(),
+        0
+    );
+    assert_eq!(
+        decode_to_buf_with_options("0x", &mut buf, options).unwrap(),
+        0
+    );
+    assert!(decode_to_buf_with_options("fg", &mut buf, options).is_err());
+}
+
+#[test]
+fn decode_options_pad_left_with_separators_and_prefix() {
+    let options = DecodeOptions::new()
+        .with_odd_length(OddLengthPolicy::PadLeft)
+        .with_separators(" ");
+    let mut buf = [0u8; 3];
+    assert!(decode_to_buf_exact_with_options("1 23 45", &mut buf, options).is_ok());
+    assert_eq!(buf, [0x01, 0x23, 0x45]);
+    assert!(decode_to_buf_exact_with_options("012345", &mut buf, options).is_ok());
+    assert_eq!(buf, [0x01, 0x23, 0x45]);
+    let options = options.with_prefix(PrefixPolicy::Forbidden);
+    assert!(decode_to_buf_exact_with_options("01 2345", &mut buf, options).is_ok());
+    assert_eq!(buf, [0x01, 0x23, 0x45]);
+    assert!(decode_to_buf_exact_with_options("0x1234", &mut buf, options).is_err());
+}
+
+#[test]
+fn decode_options_odd_length_reject_is_default() {
+    let mut buf = [0u8; 2];
+    assert!(decode_to_buf_with_options("fff", &mut buf, DecodeOptions::default()).is_err());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn decode_with_options_pad_left() {
+    let options = DecodeOptions::new().with_odd_length(OddLengthPolicy::PadLeft);
+    assert_eq!(
+        decode_with_options("fff", options).unwrap(),
+        vec![0x0f, 0xff]
+    );
+    assert_eq!(
+        decode_ascii_with_options(b"0xf", options).unwrap(),
+        vec![0x0f]
+    );
+    assert!(decode("fff").is_err());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn decode_with_options_1() {
+    let options = DecodeOptions::new()
+        .with_prefix(PrefixPolicy::Forbidden)
+        .with_case(CasePolicy::Lower)
+        .with_separators(" ");
+    assert_eq!(
+        decode_with_options("c0 ff ee", options).unwrap(),
+        vec![0xc0, 0xff, 0xee]
+    );
+    assert_eq!(
+        decode_ascii_with_options(b"c0 ff ee", options).unwrap(),
+        vec![0xc0, 0xff, 0xee]
+    );
+    assert!(decode_with_options("0xc0ffee", options).is_err());
+    assert!(decode_ascii_with_options(b"c0 FF ee", options).is_err());
+}
+
+#[test]
+fn hex_decode_iterator_with_options() {
+    use fallible_iterator::FallibleIterator;
+    let options = DecodeOptions::new().with_separators("-");
+    let mut iter = HexDecodeIterator::new_with_options(
+        fallible_iterator::convert("0x12-34".char_indices().map(Ok)),
+        options,
+    );
+    assert_eq!(iter.next().unwrap(), Some(0x12));
+    assert_eq!(iter.next().unwrap(), Some(0x34));
+    assert_eq!(iter.next().unwrap(), None);
+    // the plain constructor does not accept a prefix
+    let mut iter = HexDecodeAsciiIterator::new(fallible_iterator::convert(
+        b"0x12".iter().copied().enumerate().map(Ok),
+    ));
+    assert!(iter.next().is_err());
+}
+
 #[cfg(feature = "std")]
 #[test]
 fn decode_ascii_empty() {
@@ -394,6 +582,1794 @@ fn decode_double_prefix() {
     assert!(decode("0x0x1234").is_err());
 }
 
+#[cfg(feature = "std")]
+#[test]
+fn decode_dump_xxd() {
+    let dump = "00000000: 4865 6c6c 6f2c 2077 6f72 6c64 210a       Hello, world!.\n";
+    assert_eq!(decode_dump(dump).unwrap(), b"Hello, world!\n");
+    let dump = "\
+00000000: 48656c6c 6f2c2077  Hello, w
+00000008: 6f726c64 210a      orld!.
+";
+    assert_eq!(decode_dump(dump).unwrap(), b"Hello, world!\n");
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn decode_dump_xxd_text_column_looks_like_hex() {
+    let dump = "00000000: 6162 6364  abcd\n";
+    assert_eq!(decode_dump(dump).unwrap(), b"abcd");
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn decode_dump_xxd_repeat() {
+    let dump = "\
+00000000: 0000 0000 0000 0000 0000 0000 0000 0000  ................
+*
+00000040: 6162 63                                  abc
+";
+    let mut expected = vec![0u8; 64];
+    expected.extend_from_slice(b"abc");
+    assert_eq!(decode_dump(dump).unwrap(), expected);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn decode_dump_hexdump() {
+    let dump = "\
+00000000  48 65 6c 6c 6f 2c 20 77  6f 72 6c 64 21 0a        |Hello, world!.|
+0000000e
+";
+    assert_eq!(decode_dump(dump).unwrap(), b"Hello, world!\n");
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn decode_dump_hexdump_repeat_at_end() {
+    let dump = "\
+00000000  ff ff ff ff ff ff ff ff  ff ff ff ff ff ff ff ff  |................|
+*
+00000030
+";
+    assert_eq!(decode_dump(dump).unwrap(), vec![0xff; 48]);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn decode_dump_empty() {
+    assert_eq!(decode_dump("").unwrap(), vec![]);
+    assert_eq!(decode_dump("00000000\n").unwrap(), vec![]);
+}
+
+#[test]
+fn decode_dump_errors() {
+    use fallible_iterator::FallibleIterator;
+    let error = |dump| {
+        DumpDecodeIterator::new(dump)
+            .fold((), |(), _| Ok(()))
+            .unwrap_err()
+    };
+
+    let e = error("00000000: 4865 6c6x  Hel.\n");
+    assert_eq!((e.line(), e.column()), (1, 19));
+    assert_eq!(
+        e.kind(),
+        FromDumpErrorKind::Hex(FromHexErrorKind::UnexpectedCharacter('x'))
+    );
+
+    let e = error("00000000: 4142  AB\n00000004: 4344  CD\n");
+    assert_eq!((e.line(), e.column()), (2, 1));
+    assert_eq!(
+        e.kind(),
+        FromDumpErrorKind::UnexpectedOffset {
+            expected: 2,
+            found: 4
+        }
+    );
+    assert_eq!(
+        e.to_string(),
+        "error converting from hex dump: expected offset 0x2, found 0x4 at line 2, column 1"
+    );
+
+    let e = error("00000000: 4142  AB\n00000001: 4344  CD\n");
+    assert_eq!(e.line(), 2);
+
+    let e = error("*\n00000010: 4142  AB\n");
+    assert_eq!(
+        (e.line(), e.kind()),
+        (1, FromDumpErrorKind::UnresolvedRepeat)
+    );
+
+    let e = error("00000000: 4142 43  ABC\n*\n00000004: 4344  CD\n");
+    assert_eq!(
+        (e.line(), e.kind()),
+        (3, FromDumpErrorKind::UnresolvedRepeat)
+    );
+
+    let e = error("00000000: 4142  AB\n*\n");
+    assert_eq!(
+        (e.line(), e.kind()),
+        (2, FromDumpErrorKind::UnresolvedRepeat)
+    );
+
+    let e = error("0000000g: 4142  AB\n");
+    assert_eq!((e.line(), e.column()), (1, 8));
+    assert_eq!(e.kind(), FromDumpErrorKind::InvalidOffset);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn decode_dump_max_len() {
+    use fallible_iterator::FallibleIterator;
+    let e = decode_dump("00000000: 00  .\n*\nffffffffffff: 00  .\n").unwrap_err();
+    assert_eq!(
+        (e.line(), e.column(), e.kind()),
+        (3, 1, FromDumpErrorKind::TooLong)
+    );
+
+    let dump = "00000000: 0000  ..\n*\n00000008: 0102  ..\n";
+    let decode = |max_len| -> Result<std::vec::Vec<u8>, _> {
+        DumpDecodeIterator::new(dump)
+            .with_max_len(max_len)
+            .collect()
+    };
+    assert_eq!(decode(10).unwrap(), [0, 0, 0, 0, 0, 0, 0, 0, 1, 2]);
+    let e = decode(9).unwrap_err();
+    assert_eq!((e.line(), e.kind()), (3, FromDumpErrorKind::TooLong));
+    assert_eq!(
+        e.to_string(),
+        "error converting from hex dump: data too long at line 3, column 1"
+    );
+    let e = decode(7).unwrap_err();
+    assert_eq!((e.line(), e.kind()), (3, FromDumpErrorKind::TooLong));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn memory_image_merge() {
+    let mut image = MemoryImage::new();
+    assert!(image.is_empty());
+    image.insert(10, &[1, 2]);
+    image.insert(20, &[3]);
+    image.insert(5, &[9; 5]);
+    assert_eq!(
+        image.segments().collect::<std::vec::Vec<_>>(),
+        [(5, &[9, 9, 9, 9, 9, 1, 2][..]), (20, &[3][..])]
+    );
+    image.insert(11, &[4; 10]);
+    assert_eq!(
+        image.segments().collect::<std::vec::Vec<_>>(),
+        [(5, &[9, 9, 9, 9, 9, 1, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4][..])]
+    );
+    assert_eq!(image.len(), 16);
+    image.insert(u32::MAX, &[1, 2, 3]);
+    assert_eq!(image.get(u32::MAX), Some(1));
+    assert_eq!(image.len(), 17);
+}
+
+#[test]
+fn ihex_parse_records() {
+    use ihex::{parse_record, Record};
+    let record = parse_record(":10010000214601360121470136007EFE09D2190140").unwrap();
+    let Record::Data { offset, data } = record else {
+        panic!("expected a data record, got {record:?}");
+    };
+    assert_eq!(offset, 0x0100);
+    assert_eq!(
+        &*data,
+        &[
+            0x21, 0x46, 0x01, 0x36, 0x01, 0x21, 0x47, 0x01, 0x36, 0x00, 0x7E, 0xFE, 0x09, 0xD2,
+            0x19, 0x01
+        ]
+    );
+    assert_eq!(
+        record.to_string(),
+        ":10010000214601360121470136007EFE09D2190140"
+    );
+    assert_eq!(parse_record(":00000001FF").unwrap(), Record::EndOfFile);
+    assert_eq!(
+        parse_record(":020000021200EA").unwrap(),
+        Record::ExtendedSegmentAddress(0x1200)
+    );
+    assert_eq!(
+        parse_record(":0400000300003800C1").unwrap(),
+        Record::StartSegmentAddress {
+            cs: 0x0000,
+            ip: 0x3800
+        }
+    );
+    assert_eq!(
+        parse_record(":04000005000000CD2A").unwrap(),
+        Record::StartLinearAddress(0xCD)
+    );
+    // lower case is fine, too
+    assert_eq!(parse_record(":00000001ff").unwrap(), Record::EndOfFile);
+}
+
+#[test]
+fn ihex_parse_record_errors() {
+    use ihex::{parse_record, FromIhexErrorKind};
+    let e = parse_record("00000001FF").unwrap_err();
+    assert_eq!(
+        (e.column(), e.kind()),
+        (1, FromIhexErrorKind::MissingStartCode)
+    );
+    let e = parse_record(":00000001FE").unwrap_err();
+    assert_eq!(
+        (e.column(), e.kind()),
+        (
+            10,
+            FromIhexErrorKind::ChecksumMismatch {
+                expected: 0xFF,
+                found: 0xFE
+            }
+        )
+    );
+    assert_eq!(
+        e.to_string(),
+        "error converting from Intel HEX: expected checksum 0xff, found 0xfe at line 1, column 10"
+    );
+    let e = parse_record(":0100000101FD").unwrap_err();
+    assert_eq!(
+        (e.column(), e.kind()),
+        (8, FromIhexErrorKind::InvalidRecord)
+    );
+    let e = parse_record(":00000006FA").unwrap_err();
+    assert_eq!(
+        (e.column(), e.kind()),
+        (8, FromIhexErrorKind::UnsupportedRecordType(6))
+    );
+    let e = parse_record(":0200000001FF").unwrap_err();
+    assert_eq!(
+        (e.column(), e.kind()),
+        (2, FromIhexErrorKind::InvalidLength)
+    );
+    let e = parse_record(":00000g01FF").unwrap_err();
+    assert_eq!(
+        (e.column(), e.kind()),
+        (
+            7,
+            FromIhexErrorKind::Hex(FromHexErrorKind::UnexpectedCharacter('g'))
+        )
+    );
+}
+
+#[test]
+fn ihex_file_errors() {
+    use fallible_iterator::FallibleIterator;
+    use ihex::{FromIhexErrorKind, RecordIterator};
+    let e = RecordIterator::new(":00000001FF\n:00000001FF\n")
+        .count()
+        .unwrap_err();
+    assert_eq!(
+        (e.line(), e.kind()),
+        (2, FromIhexErrorKind::DataAfterEndOfFile)
+    );
+    let e = RecordIterator::new(":0400000001020304F2\n")
+        .count()
+        .unwrap_err();
+    assert_eq!(
+        (e.line(), e.kind()),
+        (2, FromIhexErrorKind::MissingEndOfFile)
+    );
+    for (file, line) in [("", 1), ("\n", 2), (":0400000001020304F2\n\n\n", 4)] {
+        let e = RecordIterator::new(file).count().unwrap_err();
+        assert_eq!(
+            (e.line(), e.column(), e.kind()),
+            (line, 1, FromIhexErrorKind::MissingEndOfFile),
+            "{file:?}"
+        );
+    }
+    let e = RecordIterator::new("\n\n:0400000001020304F3\n")
+        .count()
+        .unwrap_err();
+    assert_eq!(e.line(), 3);
+    let e = ihex::DataIterator::new(":02000004FFFFFC\n:02FFFF000102FD\n:00000001FF\n")
+        .count()
+        .unwrap_err();
+    assert_eq!(
+        (e.line(), e.kind()),
+        (2, FromIhexErrorKind::AddressOverflow)
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn ihex_read() {
+    let file = "\
+:10010000214601360121470136007EFE09D2190140
+:100110002146017E17C20001FF5F16002148011928
+:10012000194E79234623965778239EDA3F01B2CAA7
+:100130003F0156702B5E712B722B732146013421C7
+:00000001FF
+";
+    let ihex = ihex::read(file).unwrap();
+    let segments: std::vec::Vec<_> = ihex.image.segments().collect();
+    assert_eq!(segments.len(), 1);
+    assert_eq!(segments[0].0, 0x0100);
+    assert_eq!(segments[0].1.len(), 64);
+    assert_eq!(ihex.image.get(0x013f), Some(0x21));
+    assert_eq!(ihex.start_address, None);
+    assert_eq!(ihex.to_string(), file);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn ihex_read_segment_address() {
+    let file = ":020000021000EC\n:0100100042AD\n:0400000300003800C1\n:00000001FF\n";
+    let ihex = ihex::read(file).unwrap();
+    assert_eq!(ihex.image.get(0x10010), Some(0x42));
+    assert_eq!(
+        ihex.start_address,
+        Some(ihex::StartAddress::Segment {
+            cs: 0x0000,
+            ip: 0x3800
+        })
+    );
+}
+
+#[test]
+fn ihex_segment_address_wraps() {
+    use fallible_iterator::FallibleIterator;
+    let file = "\
+:020000021000EC
+:04FFFE0001020304F5
+:020000040001F9
+:04FFFE0001020304F5
+:00000001FF
+";
+    let mut data = ihex::DataIterator::new(file);
+    let (address, bytes) = data.next().unwrap().unwrap();
+    assert_eq!((address, &*bytes), (0x1fffe, &[1, 2][..]));
+    let (address, bytes) = data.next().unwrap().unwrap();
+    assert_eq!((address, &*bytes), (0x10000, &[3, 4][..]));
+    let (address, bytes) = data.next().unwrap().unwrap();
+    assert_eq!((address, &*bytes), (0x1fffe, &[1, 2, 3, 4][..]));
+    assert!(data.next().unwrap().is_none());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn ihex_write_roundtrip() {
+    let mut original = ihex::IntelHex::default();
+    original
+        .image
+        .insert(0x0800_fff0, &(0u8..=255).collect::<std::vec::Vec<_>>());
+    original.image.insert(0x2000_0000, &[1, 2, 3]);
+    original.start_address = Some(ihex::StartAddress::Linear(0x0800_0101));
+    for record_length in [1, 16, 32, 255] {
+        let mut file = std::string::String::new();
+        original
+            .write(&mut file, ihex::WriteOptions { record_length })
+            .unwrap();
+        for line in file.lines().filter(|line| &line[7..9] == "00") {
+            assert!(line.len() <= 11 + 2 * record_length as usize);
+        }
+        assert_eq!(ihex::read(&file).unwrap(), original);
+    }
+}
+
+#[test]
+fn srec_parse_records() {
+    use srec::{parse_record, AddressWidth, Record};
+    let record = parse_record("S1137AF00A0A0D0000000000000000000000000061").unwrap();
+    let Record::Data {
+        address,
+        width,
+        data,
+    } = record
+    else {
+        panic!("expected a data record, got {record:?}");
+    };
+    assert_eq!((address, width), (0x7AF0, AddressWidth::Bits16));
+    assert_eq!(&data[..4], &[0x0A, 0x0A, 0x0D, 0x00]);
+    assert_eq!(data.len(), 16);
+    assert_eq!(
+        record.to_string(),
+        "S1137AF00A0A0D0000000000000000000000000061"
+    );
+    let record = parse_record("S00F000068656C6C6F202020202000003C").unwrap();
+    assert_eq!(
+        record,
+        Record::Header(srec::RecordData::new(b"hello     \0\0").unwrap())
+    );
+    assert_eq!(record.to_string(), "S00F000068656C6C6F202020202000003C");
+    assert_eq!(parse_record("S5030003F9").unwrap(), Record::Count(3));
+    assert_eq!(parse_record("S604000003F8").unwrap(), Record::Count(3));
+    assert_eq!(
+        parse_record("S9030000FC").unwrap(),
+        Record::StartAddress {
+            address: 0,
+            width: AddressWidth::Bits16
+        }
+    );
+    assert_eq!(
+        parse_record("S70500000000FA").unwrap(),
+        Record::StartAddress {
+            address: 0,
+            width: AddressWidth::Bits32
+        }
+    );
+}
+
+#[test]
+fn srec_display_out_of_range() {
+    use core::fmt::Write;
+    use srec::{AddressWidth, Record, RecordData};
+    let display = |record: Record| write!(std::string::String::new(), "{record}").map(|_| ());
+    let data = RecordData::new(&[0xab]).unwrap();
+    for (address, width) in [
+        (0x1_0000, AddressWidth::Bits16),
+        (0x12345, AddressWidth::Bits16),
+        (0x100_0000, AddressWidth::Bits24),
+    ] {
+        assert!(display(Record::Data {
+            address,
+            width,
+            data
+        })
+        .is_err());
+        assert!(display(Record::StartAddress { address, width }).is_err());
+    }
+    assert!(display(Record::Count(0x100_0000)).is_err());
+    assert_eq!(Record::Count(0xFF_FFFF).to_string(), "S604FFFFFFFE");
+    let record = Record::Data {
+        address: 0xFFFF,
+        width: AddressWidth::Bits16,
+        data,
+    };
+    assert_eq!(record.to_string(), "S104FFFFAB52");
+}
+
+#[test]
+fn srec_parse_record_errors() {
+    use srec::{parse_record, FromSrecErrorKind};
+    let e = parse_record(":030000FC").unwrap_err();
+    assert_eq!(
+        (e.column(), e.kind()),
+        (1, FromSrecErrorKind::MissingStartCode)
+    );
+    let e = parse_record("S4030000FC").unwrap_err();
+    assert_eq!(
+        (e.column(), e.kind()),
+        (2, FromSrecErrorKind::UnsupportedRecordType('4'))
+    );
+    let e = parse_record("S9030000FD").unwrap_err();
+    assert_eq!(
+        (e.column(), e.kind()),
+        (
+            9,
+            FromSrecErrorKind::ChecksumMismatch {
+                expected: 0xFC,
+                found: 0xFD
+            }
+        )
+    );
+    assert_eq!(
+        e.to_string(),
+        "error converting from S-record: expected checksum 0xfc, found 0xfd at line 1, column 9"
+    );
+    let e = parse_record("S9040000FC").unwrap_err();
+    assert_eq!(
+        (e.column(), e.kind()),
+        (3, FromSrecErrorKind::InvalidLength)
+    );
+    let e = parse_record("S30400000000FB").unwrap_err();
+    assert_eq!(
+        (e.column(), e.kind()),
+        (3, FromSrecErrorKind::InvalidLength)
+    );
+    let e = parse_record("S904000001FA").unwrap_err();
+    assert_eq!(
+        (e.column(), e.kind()),
+        (9, FromSrecErrorKind::InvalidRecord)
+    );
+    let e = parse_record("S3060000000x00FB").unwrap_err();
+    assert_eq!(
+        (e.column(), e.kind()),
+        (
+            12,
+            FromSrecErrorKind::Hex(FromHexErrorKind::UnexpectedCharacter('x'))
+        )
+    );
+    let e = parse_record("S307FFFFFFFF0102F9").unwrap_err();
+    assert_eq!(e.kind(), FromSrecErrorKind::AddressOverflow);
+}
+
+#[test]
+fn srec_count_mismatch() {
+    use fallible_iterator::FallibleIterator;
+    use srec::{DataIterator, FromSrecErrorKind};
+    let file = "S107000001020304EE\n\nS5030002FA\n";
+    let e = DataIterator::new(file).count().unwrap_err();
+    assert_eq!(
+        (e.line(), e.kind()),
+        (
+            3,
+            FromSrecErrorKind::CountMismatch {
+                expected: 1,
+                found: 2
+            }
+        )
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn srec_read() {
+    let file = "\
+S00F000068656C6C6F202020202000003C
+S11F00007C0802A6900100049421FFF07C6C1B787C8C23783C6000003863000026
+S11F001C4BFFFFE5398000007D83637880010014382100107C0803A64E800020E9
+S111003848656C6C6F20776F726C642E0A0042
+S5030003F9
+S9030000FC
+";
+    let srec = srec::read(file).unwrap();
+    assert_eq!(srec.header, b"hello     \0\0");
+    let segments: std::vec::Vec<_> = srec.image.segments().collect();
+    assert_eq!(segments.len(), 1);
+    assert_eq!(segments[0].0, 0);
+    assert_eq!(segments[0].1.len(), 0x46);
+    assert_eq!(&segments[0].1[0x38..0x44], b"Hello world.");
+    assert_eq!(srec.start_address, Some(0));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn srec_write_roundtrip() {
+    use srec::{AddressWidth, SRecordFile, WriteOptions};
+    let mut original = SRecordFile {
+        header: b"test".to_vec(),
+        ..Default::default()
+    };
+    original
+        .image
+        .insert(0x00ff_fff0, &(0u8..=255).collect::<std::vec::Vec<_>>());
+    original.image.insert(0x2000_0000, &[1, 2, 3]);
+    original.start_address = Some(0x0800_0101);
+    for record_length in [0, 1, 16, 255] {
+        let options = WriteOptions {
+            address_width: None,
+            record_length,
+        };
+        let mut file = std::string::String::new();
+        original.write(&mut file, options).unwrap();
+        assert!(file.lines().skip(1).all(|line| line.starts_with("S3")
+            || line.starts_with("S5")
+            || line.starts_with("S7")));
+        assert_eq!(srec::read(&file).unwrap(), original);
+    }
+    let options = WriteOptions {
+        address_width: Some(AddressWidth::Bits24),
+        record_length: 16,
+    };
+    assert!(original
+        .write(&mut std::string::String::new(), options)
+        .is_err());
+    let mut small = SRecordFile::default();
+    small.image.insert(0x1234, &[0xab]);
+    assert_eq!(
+        small.to_string(),
+        "S0030000FC\nS1041234AB0A\nS5030001FB\nS9030000FC\n"
+    );
+}
+
+#[test]
+fn readmemh_words() {
+    use fallible_iterator::FallibleIterator;
+    use readmemh::{ReadOptions, WordIterator};
+    let file = "\
+// header comment
+0 1_0 /* inline */ 2f
+@8 /* multi
+line */ 0A//tail
+@ffff_ffff ff
+";
+    let words: std::vec::Vec<_> = WordIterator::new(file, ReadOptions::default())
+        .iterator()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(
+        words,
+        [
+            (0, 0x00),
+            (1, 0x10),
+            (2, 0x2f),
+            (8, 0x0a),
+            (0xffff_ffff, 0xff)
+        ]
+    );
+    let options = ReadOptions {
+        word_width: 64,
+        ..Default::default()
+    };
+    let words: std::vec::Vec<_> =
+        WordIterator::new("ffffffffffffffff 0000000000000000001", options)
+            .iterator()
+            .collect::<Result<_, _>>()
+            .unwrap();
+    assert_eq!(words, [(0, u64::MAX), (1, 1)]);
+}
+
+#[test]
+fn readmemh_errors() {
+    use fallible_iterator::FallibleIterator;
+    use readmemh::{FromMemErrorKind, ReadOptions, WordIterator};
+    let error = |file, word_width| {
+        let options = ReadOptions {
+            word_width,
+            ..Default::default()
+        };
+        let e = WordIterator::new(file, options).count().unwrap_err();
+        (e.line(), e.column(), e.kind())
+    };
+    assert_eq!(
+        error("00\n1x", 8),
+        (
+            2,
+            2,
+            FromMemErrorKind::Hex(FromHexErrorKind::UnexpectedCharacter('x'))
+        )
+    );
+    assert_eq!(
+        error("_1", 8),
+        (
+            1,
+            1,
+            FromMemErrorKind::Hex(FromHexErrorKind::UnexpectedCharacter('_'))
+        )
+    );
+    assert_eq!(error("00 100", 8), (1, 4, FromMemErrorKind::WordTooWide));
+    assert_eq!(error("fff 1000", 12), (1, 5, FromMemErrorKind::WordTooWide));
+    assert_eq!(
+        error("1_0000_0000_0000_0000", 64),
+        (1, 1, FromMemErrorKind::WordTooWide)
+    );
+    assert_eq!(
+        error("00 @ 01", 8),
+        (1, 4, FromMemErrorKind::InvalidAddress)
+    );
+    assert_eq!(
+        error("@1_0000_0000", 8),
+        (1, 2, FromMemErrorKind::AddressOverflow)
+    );
+    assert_eq!(
+        error("@ffffffff 00 01", 8),
+        (1, 14, FromMemErrorKind::AddressOverflow)
+    );
+    assert_eq!(
+        error("00\n  /* open\n01", 8),
+        (2, 3, FromMemErrorKind::UnterminatedComment)
+    );
+    assert_eq!(
+        error("00 / 01", 8),
+        (
+            1,
+            4,
+            FromMemErrorKind::Hex(FromHexErrorKind::UnexpectedCharacter('/'))
+        )
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn readmemh_read_image() {
+    use readmemh::{FromMemErrorKind, ReadOptions};
+    let options = ReadOptions {
+        word_width: 24,
+        endian: Endian::Big,
+        ..Default::default()
+    };
+    let image = readmemh::read_image("abcdef 123456 @3 7", options).unwrap();
+    let segments: std::vec::Vec<_> = image.segments().collect();
+    assert_eq!(
+        segments,
+        [
+            (0, &[0xab, 0xcd, 0xef, 0x12, 0x34, 0x56][..]),
+            (9, &[0x00, 0x00, 0x07][..])
+        ]
+    );
+    let e = readmemh::read_image("@55555555 000000", options).unwrap_err();
+    assert_eq!(e.kind(), FromMemErrorKind::AddressOverflow);
+    assert_eq!(e.column(), 11);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn readmemh_roundtrip() {
+    use readmemh::{ReadOptions, WriteOptions};
+    let mut image = MemoryImage::new();
+    image.insert(0x1, &[1, 2, 3, 4, 5]);
+    image.insert(0x7, &[6]);
+    image.insert(0x100, &(0u8..=255).collect::<std::vec::Vec<_>>());
+    for word_width in [8, 16, 32, 64] {
+        for endian in [Endian::Big, Endian::Little] {
+            let bytes = usize::from(word_width / 8);
+            let options = WriteOptions {
+                word_width,
+                endian,
+                words_per_line: 3,
+            };
+            let mut file = std::string::String::new();
+            readmemh::write_image(&mut file, &image, options).unwrap();
+            assert!(file
+                .lines()
+                .all(|line| line.starts_with('@') || line.split(' ').count() <= 3));
+            let read = readmemh::read_image(
+                &file,
+                ReadOptions {
+                    word_width,
+                    endian,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+            for address in 0..0x200 {
+                let expected = image.get(address).or_else(|| {
+                    let word = address as usize / bytes * bytes;
+                    (word..word + bytes)
+                        .any(|a| image.get(a as u32).is_some())
+                        .then_some(0)
+                });
+                assert_eq!(read.get(address), expected, "address {address:#x}");
+            }
+        }
+    }
+    let options = WriteOptions {
+        word_width: 4,
+        ..Default::default()
+    };
+    assert!(readmemh::write_words(&mut std::string::String::new(), &[0x10], options).is_err());
+    let words = [0x1, 0x2, 0xf];
+    let mut file = std::string::String::new();
+    readmemh::write_words(&mut file, &words, options).unwrap();
+    assert_eq!(file, "1 2 f\n");
+    let options = ReadOptions {
+        word_width: 4,
+        ..Default::default()
+    };
+    assert_eq!(readmemh::read_words(&file, options).unwrap(), words);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn readmemh_read_words_max_words() {
+    use readmemh::{FromMemErrorKind, ReadOptions};
+    let e = readmemh::read_words("00\n@ffffffff 01", ReadOptions::default()).unwrap_err();
+    assert_eq!(
+        (e.line(), e.column(), e.kind()),
+        (2, 11, FromMemErrorKind::TooManyWords)
+    );
+    assert_eq!(
+        e.to_string(),
+        "error converting from memory file: too many words at line 2, column 11"
+    );
+    let options = ReadOptions {
+        max_words: 4,
+        ..Default::default()
+    };
+    assert_eq!(
+        readmemh::read_words("@3 ff", options).unwrap(),
+        [0, 0, 0, 0xff]
+    );
+    let e = readmemh::read_words("@3 ff ee", options).unwrap_err();
+    assert_eq!(
+        (e.line(), e.column(), e.kind()),
+        (1, 7, FromMemErrorKind::TooManyWords)
+    );
+    // sparse images are not limited
+    let image = readmemh::read_image("@ffffffff 01", ReadOptions::default()).unwrap();
+    assert_eq!(image.get(0xffff_ffff), Some(1));
+}
+
+#[test]
+fn decode_array_const() {
+    const EMPTY: [u8; 0] = decode_array("0x");
+    const KEY: [u8; 8] = decode_array("0123456789abcDEF");
+    static MAGIC: [u8; 2] = decode_array("0xCafe");
+    assert_eq!(EMPTY, []);
+    assert_eq!(KEY, [0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef]);
+    assert_eq!(MAGIC, [0xca, 0xfe]);
+    const ERROR: Result<[u8; 2], FromHexError> = try_decode_array("c0fé");
+    assert_eq!(
+        ERROR.unwrap_err().kind,
+        FromHexErrorKind::UnexpectedCharacter('é')
+    );
+}
+
+#[test]
+fn decode_array_matches_decode_to_buf_exact() {
+    for hex in [
+        "", "0x", "12", "0x12", "1234", "0x1234", "123456", "0x123", "123", "1", "0X12", "0x0x",
+        "12g4", "12 34", "x12", "1ö", "ö1", "0xΣ", "00\n",
+    ] {
+        let mut buf = [0u8; 2];
+        let expected = decode_to_buf_exact(hex, &mut buf).map(|()| buf);
+        let actual = try_decode_array::<2>(hex);
+        match (expected, actual) {
+            (Ok(expected), Ok(actual)) => assert_eq!(expected, actual, "{hex:?}"),
+            (Err(expected), Err(actual)) => assert_eq!(expected.kind, actual.kind, "{hex:?}"),
+            (expected, actual) => panic!("{hex:?}: expected {expected:?}, got {actual:?}"),
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "too short")]
+fn decode_array_panics() {
+    decode_array::<3>("1234");
+}
+
+#[test]
+fn encode_array_const() {
+    const EMPTY: [u8; 0] = encode_array(&[], Case::Lower);
+    const ALL: [u8; 512] = {
+        let mut bytes = [0u8; 256];
+        let mut i = 0;
+        while i < 256 {
+            bytes[i] = i as u8;
+            i += 1;
+        }
+        encode_array(&bytes, Case::Upper)
+    };
+    static ALL_STR: &str = encoded_str(&ALL);
+    assert_eq!(encoded_str(&EMPTY), "");
+    let all: [u8; 256] = core::array::from_fn(|i| i as u8);
+    assert_eq!(ALL_STR, Hex::new(all).with_case(Case::Upper).to_string());
+    assert_eq!(
+        encoded_str(&encode_array::<256, 512>(&all, Case::Lower)),
+        hex(all).to_string()
+    );
+}
+
+#[test]
+#[should_panic(expected = "twice as long")]
+fn encode_array_panics() {
+    encode_array::<2, 5>(&[1, 2], Case::Lower);
+}
+
+#[test]
+fn error_line_column() {
+    let error = |position| FromHexError {
+        position,
+        kind: FromHexErrorKind::OddLength,
+    };
+    let input = "ab\ncd\r\n\nöx";
+    assert_eq!(error(0).line_column(input), (1, 1));
+    assert_eq!(error(2).line_column(input), (1, 3));
+    assert_eq!(error(3).line_column(input), (2, 1));
+    assert_eq!(error(5).line_column(input), (2, 3));
+    assert_eq!(error(7).line_column(input), (3, 1));
+    assert_eq!(error(8).line_column(input), (4, 1));
+    assert_eq!(error(10).line_column(input), (4, 2));
+    assert_eq!(error(11).line_column(input), (4, 3));
+    assert_eq!(error(0).line_column(""), (1, 1));
+}
+
+#[test]
+fn error_snippet() {
+    let input = "00\n".repeat(9) + "\t0 0x1\r\n";
+    let options = DecodeOptions::new().with_separators("\n\r\t");
+    let mut buf = [0u8; 16];
+    let err = decode_to_buf_with_options(&input, &mut buf, options).unwrap_err();
+    assert_eq!(
+        err.snippet(&input).to_string(),
+        "\
+error converting from hex: unexpected character   at line 10, column 3
+   |
+10 | \t0 0x1
+   | \t ^
+"
+    );
+    let err = decode_to_buf("abc", &mut buf).unwrap_err();
+    assert_eq!(
+        err.snippet("abc").to_string(),
+        "\
+error converting from hex: odd number of hex digits at line 1, column 3
+  |
+1 | abc
+  |   ^
+"
+    );
+}
+
+#[cfg(feature = "miette")]
+#[test]
+fn error_miette_labels() {
+    use miette::Diagnostic;
+    let mut buf = [0u8; 4];
+    let err = decode_to_buf("c0€e", &mut buf).unwrap_err();
+    let labels: std::vec::Vec<_> = err.labels().unwrap().collect();
+    assert_eq!(labels.len(), 1);
+    assert_eq!((labels[0].offset(), labels[0].len()), (2, 3));
+    assert_eq!(labels[0].label(), Some("unexpected character €"));
+    let report = miette::Report::new(err).with_source_code("c0€e");
+    assert_eq!(report.code().unwrap().to_string(), "hexhex::from_hex");
+}
+
+#[test]
+fn error_kinds() {
+    let error = |hex: &str, len: usize, options: DecodeOptions| {
+        let mut buf = [0u8; 8];
+        let err = decode_to_buf_with_options(hex, &mut buf[..len], options).unwrap_err();
+        (err.position(), err.kind())
+    };
+    let exact_error = |hex: &str, len: usize| {
+        let mut buf = [0u8; 8];
+        let err = decode_to_buf_exact(hex, &mut buf[..len]).unwrap_err();
+        (err.position(), err.kind())
+    };
+    let default = DecodeOptions::new();
+    assert_eq!(error("abc", 8, default), (2, FromHexErrorKind::OddLength));
+    assert_eq!(error("0xa", 8, default), (2, FromHexErrorKind::OddLength));
+    assert_eq!(
+        error("0xabcdef", 1, default),
+        (4, FromHexErrorKind::OutputTooSmall { required: 3 })
+    );
+    assert_eq!(
+        error("ab cd ef", 1, default.with_separators(" ")),
+        (3, FromHexErrorKind::OutputTooSmall { required: 3 })
+    );
+    assert_eq!(
+        error("abcdeg", 1, default),
+        (5, FromHexErrorKind::UnexpectedCharacter('g'))
+    );
+    assert_eq!(
+        exact_error("abcd", 3),
+        (4, FromHexErrorKind::OutputTooLarge)
+    );
+    assert_eq!(exact_error("0x", 1), (2, FromHexErrorKind::OutputTooLarge));
+    assert_eq!(
+        exact_error("abcd", 1),
+        (2, FromHexErrorKind::OutputTooSmall { required: 2 })
+    );
+    let required = default.with_prefix(PrefixPolicy::Required);
+    assert_eq!(
+        error("abcd", 8, required),
+        (0, FromHexErrorKind::InvalidPrefix)
+    );
+    assert_eq!(
+        error("0abc", 8, required),
+        (0, FromHexErrorKind::InvalidPrefix)
+    );
+    assert_eq!(
+        error("0", 8, required),
+        (0, FromHexErrorKind::InvalidPrefix)
+    );
+    assert_eq!(error("", 8, required), (0, FromHexErrorKind::InvalidPrefix));
+    let forbidden = default.with_prefix(PrefixPolicy::Forbidden);
+    assert_eq!(
+        error("0xab", 8, forbidden),
+        (0, FromHexErrorKind::InvalidPrefix)
+    );
+    assert_eq!(
+        error("ab0x", 8, forbidden),
+        (3, FromHexErrorKind::UnexpectedCharacter('x'))
+    );
+    let mut buf = [0u8; 1];
+    let err = decode_ascii_to_buf(b"0x12\x80", &mut buf).unwrap_err();
+    assert_eq!(
+        (err.position(), err.kind()),
+        (4, FromHexErrorKind::UnexpectedByte(0x80))
+    );
+    assert_eq!(
+        FromHexError {
+            position: 2,
+            kind: FromHexErrorKind::OutputTooSmall { required: 5 }
+        }
+        .to_string(),
+        "error converting from hex: output buffer is too small, 5 bytes required at position 2"
+    );
+}
+
+#[test]
+fn decode_array_error_kinds() {
+    let kind = |hex| try_decode_array::<2>(hex).unwrap_err().kind();
+    assert_eq!(kind("0x123"), FromHexErrorKind::OddLength);
+    assert_eq!(kind("12"), FromHexErrorKind::OutputTooLarge);
+    assert_eq!(
+        kind("0x123456"),
+        FromHexErrorKind::OutputTooSmall { required: 3 }
+    );
+}
+
+#[test]
+fn validate_all_errors() {
+    let errors = |hex, options| {
+        validate_all_with_options(hex, options)
+            .map(|e| (e.position(), e.kind()))
+            .collect::<std::vec::Vec<_>>()
+    };
+    let default = DecodeOptions::new();
+    assert_eq!(errors("", default), []);
+    assert_eq!(errors("0xc0ffee", default), []);
+    assert_eq!(
+        errors("xyc0ffee", default),
+        [
+            (0, FromHexErrorKind::UnexpectedCharacter('x')),
+            (1, FromHexErrorKind::UnexpectedCharacter('y'))
+        ]
+    );
+    assert_eq!(
+        errors("c0ffeé1", default),
+        [
+            (5, FromHexErrorKind::UnexpectedCharacter('é')),
+            (7, FromHexErrorKind::OddLength)
+        ]
+    );
+    assert_eq!(
+        errors("c0 ff\nee", default.with_separators(" ")),
+        [
+            (5, FromHexErrorKind::UnexpectedCharacter('\n')),
+            (7, FromHexErrorKind::OddLength)
+        ]
+    );
+    assert_eq!(
+        errors("c0 ff  e e", default.with_separators(" ")),
+        [
+            (8, FromHexErrorKind::UnexpectedCharacter(' ')),
+            (9, FromHexErrorKind::OddLength)
+        ]
+    );
+    assert_eq!(
+        errors("abcdEF", default.with_case(CasePolicy::Consistent)),
+        [
+            (4, FromHexErrorKind::UnexpectedCharacter('E')),
+            (5, FromHexErrorKind::UnexpectedCharacter('F'))
+        ]
+    );
+    assert_eq!(
+        errors("c0fg", default.with_prefix(PrefixPolicy::Required)),
+        [
+            (0, FromHexErrorKind::InvalidPrefix),
+            (3, FromHexErrorKind::UnexpectedCharacter('g'))
+        ]
+    );
+    assert_eq!(
+        errors("0", default.with_prefix(PrefixPolicy::Required)),
+        [
+            (0, FromHexErrorKind::InvalidPrefix),
+            (0, FromHexErrorKind::OddLength)
+        ]
+    );
+    assert_eq!(
+        errors("0xfg", default.with_prefix(PrefixPolicy::Forbidden)),
+        [
+            (0, FromHexErrorKind::InvalidPrefix),
+            (3, FromHexErrorKind::UnexpectedCharacter('g'))
+        ]
+    );
+    assert_eq!(
+        errors("g", default.with_odd_length(OddLengthPolicy::PadLeft)),
+        [(0, FromHexErrorKind::UnexpectedCharacter('g'))]
+    );
+    assert_eq!(
+        errors("gabc", default.with_odd_length(OddLengthPolicy::PadLeft)),
+        [(0, FromHexErrorKind::UnexpectedCharacter('g'))]
+    );
+}
+
+#[test]
+fn validate_all_first_error_matches_decode() {
+    let options = DecodeOptions::new().with_separators(":");
+    for hex in [
+        "", "0x", "0xg", "12:34", "12:3", "1:234", "0x12:x4", "€", "12€", "a", "ab:", ":ab:cd:e",
+    ] {
+        let mut buf = [0u8; 8];
+        let expected = decode_to_buf_with_options(hex, &mut buf, options)
+            .err()
+            .map(|e| (e.position(), e.kind()));
+        let actual = validate_all_with_options(hex, options)
+            .next()
+            .map(|e| (e.position(), e.kind()));
+        assert_eq!(expected, actual, "{hex:?}");
+    }
+    let mut count = 0;
+    assert_eq!(validate_all_with_callback("xx", options, |_| count += 1), 2);
+    assert_eq!(count, 2);
+}
+
+#[test]
+fn validate_matches_decode_to_buf() {
+    let options = DecodeOptions::new()
+        .with_separators(" ")
+        .with_odd_length(OddLengthPolicy::PadLeft);
+    for (hex, options) in [
+        ("", DecodeOptions::new()),
+        ("0x", DecodeOptions::new()),
+        ("0xc0ffee", DecodeOptions::new()),
+        ("c0ffee", DecodeOptions::new()),
+        ("c0ffe", DecodeOptions::new()),
+        ("c0ffeg", DecodeOptions::new()),
+        ("c0€fee", DecodeOptions::new()),
+        ("0xfff ff", options),
+        ("0xfff f", options),
+        (
+            "c0ffee",
+            DecodeOptions::new().with_prefix(PrefixPolicy::Required),
+        ),
+    ] {
+        let mut buf = [0u8; 8];
+        let expected = decode_to_buf_with_options(hex, &mut buf, options).map_err(|e| e.kind());
+        assert_eq!(
+            validate_with_options(hex, options).map_err(|e| e.kind()),
+            expected,
+            "{hex:?}"
+        );
+        let expected = decode_ascii_to_buf_with_options(hex.as_bytes(), &mut buf, options)
+            .map_err(|e| (e.position(), e.kind()));
+        assert_eq!(
+            validate_ascii_with_options(hex.as_bytes(), options)
+                .map_err(|e| (e.position(), e.kind())),
+            expected,
+            "{hex:?}"
+        );
+    }
+}
+
+#[test]
+fn decoded_and_encoded_len() {
+    const BUF: [u8; decoded_len(7)] = [0; 4];
+    assert_eq!(BUF.len(), 4);
+    assert_eq!(decoded_len(0), 0);
+    assert_eq!(decoded_len(6), 3);
+    assert_eq!(encoded_len(0), 0);
+    assert_eq!(encoded_len(3), 6);
+    let bytes = [0xab; 5];
+    assert_eq!(hex(bytes).to_string().len(), encoded_len(bytes.len()));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn decode_into_vec_appends() {
+    let mut bytes = vec![0x01];
+    assert_eq!(decode_into("0xc0ffee", &mut bytes).unwrap(), 3);
+    assert_eq!(bytes, [0x01, 0xc0, 0xff, 0xee]);
+    assert_eq!(decode_ascii_into(b"", &mut bytes).unwrap(), 0);
+    let options = DecodeOptions::new()
+        .with_separators(" ")
+        .with_odd_length(OddLengthPolicy::PadLeft);
+    assert_eq!(
+        decode_ascii_into_with_options(b"f ff", &mut bytes, options).unwrap(),
+        2
+    );
+    assert_eq!(bytes, [0x01, 0xc0, 0xff, 0xee, 0x0f, 0xff]);
+    // nothing is appended on error
+    let err = decode_into("c0ffeg", &mut bytes).unwrap_err();
+    assert_eq!(err.kind(), FromHexErrorKind::UnexpectedCharacter('g'));
+    assert_eq!(err.position(), 5);
+    assert_eq!(bytes.len(), 6);
+}
+
+#[cfg(feature = "bytes")]
+#[test]
+fn decode_into_bytes() {
+    let mut bytes = bytes::BytesMut::new();
+    assert_eq!(decode_into("c0ffee", &mut bytes).unwrap(), 3);
+    assert_eq!(&bytes[..], [0xc0, 0xff, 0xee]);
+
+    let mut buf = [0u8; 2];
+    let mut sink = BufMutSink(&mut buf[..]);
+    let err = decode_into("c0ffee", &mut sink).unwrap_err();
+    assert_eq!(err.kind(), FromHexErrorKind::OutputTooSmall { required: 3 });
+    assert_eq!(decode_into("c0ff", &mut sink).unwrap(), 2);
+    assert_eq!(buf, [0xc0, 0xff]);
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn decode_into_heapless() {
+    let mut bytes = heapless::Vec::<u8, 4>::new();
+    assert_eq!(decode_into("c0ff", &mut bytes).unwrap(), 2);
+    let err = decode_ascii_into(b"eeee00", &mut bytes).unwrap_err();
+    assert_eq!(err.kind(), FromHexErrorKind::OutputTooSmall { required: 3 });
+    assert_eq!(err.position(), 0);
+    assert_eq!(decode_ascii_into(b"eeee", &mut bytes).unwrap(), 2);
+    assert_eq!(bytes, [0xc0, 0xff, 0xee, 0xee]);
+}
+
+#[cfg(feature = "arrayvec")]
+#[test]
+fn decode_into_arrayvec() {
+    let mut bytes = arrayvec::ArrayVec::<u8, 4>::new();
+    assert_eq!(decode_into("c0ff", &mut bytes).unwrap(), 2);
+    let err = decode_into("eeee00", &mut bytes).unwrap_err();
+    assert_eq!(err.kind(), FromHexErrorKind::OutputTooSmall { required: 3 });
+    assert_eq!(decode_into("eeee", &mut bytes).unwrap(), 2);
+    assert_eq!(bytes.as_slice(), [0xc0, 0xff, 0xee, 0xee]);
+}
+
+#[test]
+fn decode_in_place_matches_decode_ascii_to_buf() {
+    let options = DecodeOptions::new()
+        .with_separators(" \n")
+        .with_odd_length(OddLengthPolicy::PadLeft);
+    for (hex, options) in [
+        ("", DecodeOptions::new()),
+        ("0x", DecodeOptions::new()),
+        ("0xc0ffee", DecodeOptions::new()),
+        ("C0FFEE", DecodeOptions::new()),
+        ("c0ffe", DecodeOptions::new()),
+        ("c0ffeg", DecodeOptions::new()),
+        ("0xfff ff", options),
+        ("f", options),
+        ("0 1\n2 3 4", options),
+        (
+            "c0ffee",
+            DecodeOptions::new().with_prefix(PrefixPolicy::Required),
+        ),
+        (
+            "0xc0ffee",
+            DecodeOptions::new().with_prefix(PrefixPolicy::Forbidden),
+        ),
+    ] {
+        let mut dst = [0u8; 8];
+        let expected = decode_ascii_to_buf_with_options(hex.as_bytes(), &mut dst, options)
+            .map(|len| &dst[..len])
+            .map_err(|e| (e.position(), e.kind()));
+        let mut buf = [0u8; 16];
+        let buf = &mut buf[..hex.len()];
+        buf.copy_from_slice(hex.as_bytes());
+        let actual = decode_in_place_with_options(buf, options)
+            .map(|bytes| &*bytes)
+            .map_err(|e| (e.position(), e.kind()));
+        assert_eq!(actual, expected, "{hex:?}");
+    }
+    let mut buf = *b"c0ffee";
+    assert_eq!(decode_in_place(&mut buf).unwrap(), [0xc0, 0xff, 0xee]);
+    assert_eq!(buf, [0xc0, 0xff, 0xee, b'f', b'e', b'e']);
+}
+
+#[test]
+fn encode_in_place_matches_hex() {
+    let data = [0x00, 0x01, 0x7f, 0x80, 0xab, 0xcd, 0xef, 0xff];
+    for len in 0..=data.len() {
+        for case in [Case::Lower, Case::Upper] {
+            let mut buf = [0u8; 16];
+            buf[..len].copy_from_slice(&data[..len]);
+            let expected = Hex::new(&data[..len]).with_case(case).to_string();
+            assert_eq!(encode_in_place(&mut buf[..2 * len], case), expected);
+        }
+    }
+    // odd length: the last byte is not used
+    let mut buf = [0xc0, 0xff, b'?', b'?', b'!'];
+    assert_eq!(encode_in_place(&mut buf, Case::Lower), "c0ff");
+    assert_eq!(buf[4], b'!');
+    assert_eq!(encode_in_place(&mut [0xaa], Case::Lower), "");
+}
+
+#[test]
+fn ct_encode_matches_encode_array() {
+    for byte in 0..=255u8 {
+        for case in [Case::Lower, Case::Upper] {
+            let mut hex = [0u8; 2];
+            ct::encode(&[byte], &mut hex, case).unwrap();
+            assert_eq!(hex, encode_array::<1, 2>(&[byte], case), "{byte:#x}");
+        }
+    }
+    let bytes = [0x00, 0x09, 0x0a, 0x0f, 0x90, 0xa0, 0xf0, 0xff];
+    let mut hex = [0u8; 16];
+    ct::encode(&bytes, &mut hex, Case::Upper).unwrap();
+    assert_eq!(&hex, b"00090A0F90A0F0FF");
+    assert_eq!(
+        ct::encode(&bytes, &mut hex[1..], Case::Lower),
+        Err(ct::Error)
+    );
+    assert_eq!(ct::encode(&[], &mut [], Case::Lower), Ok(()));
+}
+
+#[test]
+fn ct_decode_matches_decode_ascii_to_buf_exact() {
+    // every pair of bytes, including the "0x" prefix which the regular codec decodes to nothing
+    for high in 0..=255u8 {
+        for low in 0..=255u8 {
+            let hex = [high, low];
+            let mut expected = [0u8];
+            let expected = decode_ascii_to_buf_exact(&hex, &mut expected).map(|()| expected);
+            let mut actual = [0u8];
+            let actual = ct::decode_ascii(&hex, &mut actual).map(|()| actual);
+            assert_eq!(actual.ok(), expected.ok(), "{hex:?}");
+        }
+    }
+    let mut buf = [0u8; 3];
+    assert_eq!(ct::decode("c0FFee", &mut buf), Ok(()));
+    assert_eq!(buf, [0xc0, 0xff, 0xee]);
+    assert_eq!(ct::decode("c0ffe", &mut buf), Err(ct::Error));
+    assert_eq!(ct::decode("c0ffee00", &mut buf), Err(ct::Error));
+    assert_eq!(ct::decode("c0 fee", &mut buf), Err(ct::Error));
+    assert_eq!(ct::decode("c0ff€", &mut buf[..2]), Err(ct::Error));
+    assert_eq!(ct::decode("", &mut []), Ok(()));
+}
+
+#[cfg(all(feature = "std", feature = "zeroize"))]
+#[test]
+fn decode_zeroizing_matches_decode() {
+    for hex in ["", "0xc0ffee", "c0ffe", "c0ffeg"] {
+        let expected = decode(hex).map_err(|e| (e.position(), e.kind()));
+        let actual = decode_zeroizing(hex).map(|bytes| bytes.to_vec());
+        assert_eq!(actual.map_err(|e| (e.position(), e.kind())), expected);
+        let expected = decode_ascii(hex.as_bytes()).map_err(|e| (e.position(), e.kind()));
+        let actual = decode_ascii_zeroizing(hex.as_bytes()).map(|bytes| bytes.to_vec());
+        assert_eq!(actual.map_err(|e| (e.position(), e.kind())), expected);
+    }
+    let options = DecodeOptions::new().with_separators(" ");
+    assert_eq!(
+        *decode_zeroizing_with_options("c0 ff", options).unwrap(),
+        [0xc0, 0xff]
+    );
+}
+
+#[cfg(feature = "zeroize")]
+#[test]
+fn decode_zeroizing_arrays_and_in_place() {
+    use fallible_iterator::FallibleIterator;
+
+    assert_eq!(
+        *try_decode_array_zeroizing::<2>("c0de").unwrap(),
+        [0xc0, 0xde]
+    );
+    assert_eq!(
+        try_decode_ascii_array_zeroizing::<2>(b"c0")
+            .unwrap_err()
+            .kind(),
+        FromHexErrorKind::OutputTooLarge
+    );
+
+    let mut buf = *b"0xc0ffee";
+    assert_eq!(
+        decode_in_place_zeroizing(&mut buf).unwrap(),
+        [0xc0, 0xff, 0xee]
+    );
+    assert_eq!(buf, [0xc0, 0xff, 0xee, 0, 0, 0, 0, 0]);
+    let mut buf = *b"c0ffeg";
+    assert!(decode_in_place_zeroizing(&mut buf).is_err());
+    assert_eq!(buf, [0; 6]);
+
+    let mut input = *b"c0ffee";
+    let mut iter = HexDecodeAsciiIterator::new(ZeroizingInput::new(&mut input));
+    assert_eq!(iter.next().unwrap(), Some(0xc0));
+    assert_eq!(iter.next().unwrap(), Some(0xff));
+    assert_eq!(iter.next().unwrap(), Some(0xee));
+    assert_eq!(iter.next().unwrap(), None);
+    assert_eq!(input, [0; 6]);
+}
+
+#[test]
+fn hex_display_long_input() {
+    // longer than the internal chunk buffer
+    let bytes: [u8; 100] = core::array::from_fn(|i| i as u8);
+    let mut expected = [0u8; 200];
+    for (i, byte) in bytes.iter().enumerate() {
+        expected[2 * i..2 * i + 2].copy_from_slice(&encode_array::<1, 2>(&[*byte], Case::Upper));
+    }
+    assert_eq!(
+        Hex::new(bytes)
+            .with_case(Case::Upper)
+            .to_string()
+            .as_bytes(),
+        expected
+    );
+}
+
+#[test]
+fn decode_iter_matches_decode_to_buf() {
+    let options = DecodeOptions::new()
+        .with_separators(" ")
+        .with_odd_length(OddLengthPolicy::PadLeft);
+    for (hex, options) in [
+        ("", DecodeOptions::new()),
+        ("0x", DecodeOptions::new()),
+        ("0xc0ffee", DecodeOptions::new()),
+        ("c0ffe", DecodeOptions::new()),
+        ("c0ffeg", DecodeOptions::new()),
+        ("xxc0ff", DecodeOptions::new()),
+        ("c0€fee", DecodeOptions::new()),
+        ("0xfff ff", options),
+        ("0xfff f", options),
+        ("", DecodeOptions::new().with_prefix(PrefixPolicy::Required)),
+        (
+            "c0ffee",
+            DecodeOptions::new().with_prefix(PrefixPolicy::Required),
+        ),
+        (
+            "0xc0ffee",
+            DecodeOptions::new().with_prefix(PrefixPolicy::Forbidden),
+        ),
+    ] {
+        let mut buf = [0u8; 8];
+        let expected = decode_to_buf_with_options(hex, &mut buf, options)
+            .map(|len| &buf[..len])
+            .map_err(|e| (e.position(), e.kind()));
+        let iter = decode_iter_with_options(hex, options);
+        let len = iter.len();
+        let mut actual = [0u8; 8];
+        let mut count = 0;
+        let mut error = None;
+        for item in iter {
+            match item {
+                Ok(byte) => actual[count] = byte,
+                Err(e) => {
+                    assert!(error.is_none(), "more than one error in {hex:?}");
+                    error = Some((e.position(), e.kind()));
+                }
+            }
+            count += 1;
+        }
+        assert_eq!(count, len, "{hex:?}");
+        match expected {
+            Ok(bytes) => assert_eq!(&actual[..count], bytes, "{hex:?}"),
+            // only the error
+            Err(e) => assert_eq!((count, error), (1, Some(e)), "{hex:?}"),
+        }
+
+        let mut buf = [0u8; 8];
+        let expected = decode_ascii_to_buf_with_options(hex.as_bytes(), &mut buf, options)
+            .map_err(|e| (e.position(), e.kind()))
+            .err();
+        let actual = decode_ascii_iter_with_options(hex.as_bytes(), options)
+            .find_map(Result::err)
+            .map(|e| (e.position(), e.kind()));
+        assert_eq!(actual, expected, "{hex:?}");
+    }
+    let mut iter = decode_iter("c0ffee");
+    assert_eq!(iter.size_hint(), (3, Some(3)));
+    iter.next();
+    assert_eq!(iter.len(), 2);
+    assert_eq!(iter.nth(1).unwrap().unwrap(), 0xee);
+    assert!(iter.next().is_none());
+}
+
+#[test]
+fn hex_words_display() {
+    let bytes = [0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef, 0xa5];
+    for (word_size, endian, expected) in [
+        (2, Endian::Big, "0123 4567 89ab cdef a5"),
+        (2, Endian::Little, "2301 6745 ab89 efcd a5"),
+        (4, Endian::Big, "01234567 89abcdef a5"),
+        (4, Endian::Little, "67452301 efcdab89 a5"),
+        (8, Endian::Little, "efcdab8967452301 a5"),
+        (3, Endian::Little, "452301 ab8967 a5efcd"),
+        (16, Endian::Little, "a5efcdab8967452301"),
+        (0, Endian::Big, "01 23 45 67 89 ab cd ef a5"),
+    ] {
+        let words = hex_words(&bytes)
+            .with_word_size(word_size)
+            .with_endian(endian);
+        assert_eq!(words.to_string(), expected, "{word_size} {endian:?}");
+    }
+    let separator = std::string::String::from(", ");
+    let words = HexWords::new(&bytes[..6])
+        .with_separator(&separator)
+        .with_case(Case::Upper);
+    assert_eq!(words.to_string(), "01234567, 89AB");
+    assert_eq!(hex_words([0u8; 0]).to_string(), "");
+}
+
+#[test]
+fn nibbles_view() {
+    let bytes = [0x12, 0x34, 0xab];
+    let nibbles = Nibbles::new(&bytes);
+    assert_eq!(nibbles.len(), 6);
+    assert!(nibbles.iter().eq([1, 2, 3, 4, 0xa, 0xb]));
+    assert!(nibbles.iter().rev().eq([0xb, 0xa, 4, 3, 2, 1]));
+    assert_eq!(nibbles.get(6), None);
+    assert_eq!(nibbles.to_string(), "1234ab");
+    assert_eq!(
+        nibbles.with_case(Case::Upper).with_prefix(true).to_string(),
+        "0x1234AB"
+    );
+
+    let nibbles = nibbles.with_order(NibbleOrder::LowFirst).with_len(5);
+    assert_eq!(nibbles.iter().len(), 5);
+    assert!(nibbles.into_iter().eq([2, 1, 4, 3, 0xb]));
+    assert_eq!(nibbles[4], 0xb);
+    assert_eq!(nibbles.bytes(), bytes);
+    assert_eq!(nibbles.to_string(), "2143b");
+
+    let nibbles = Nibbles::new(&bytes).with_len(100);
+    assert_eq!(nibbles.len(), 6);
+    assert!(Nibbles::new(&[]).is_empty());
+}
+
+#[test]
+#[should_panic(expected = "index out of bounds")]
+fn nibbles_index_out_of_bounds() {
+    let _ = Nibbles::new(&[0x12]).with_len(1)[1];
+}
+
+#[test]
+fn decode_nibbles_odd_and_even() {
+    let options = DecodeOptions::new().with_separators(" ");
+    for (hex, bytes, len) in [
+        ("", &[][..], 0),
+        ("1", &[0x10][..], 1),
+        ("0x12", &[0x12][..], 2),
+        ("123", &[0x12, 0x30][..], 3),
+        ("1 23 45", &[0x12, 0x34, 0x50][..], 5),
+    ] {
+        let mut buf = [0xffu8; 4];
+        let nibbles = decode_nibbles_with_options(hex, &mut buf, options).unwrap();
+        assert_eq!((nibbles.bytes(), nibbles.len()), (bytes, len), "{hex:?}");
+        assert_eq!(
+            nibbles.to_string(),
+            hex.replace([' ', 'x'], "").trim_start_matches("0"),
+            "{hex:?}"
+        );
+        let mut buf = [0xffu8; 4];
+        let nibbles = decode_ascii_nibbles_with_options(hex.as_bytes(), &mut buf, options).unwrap();
+        assert_eq!((nibbles.bytes(), nibbles.len()), (bytes, len), "{hex:?}");
+    }
+    let mut buf = [0u8; 1];
+    let err = decode_nibbles("12g", &mut buf).unwrap_err();
+    assert_eq!(
+        (err.position(), err.kind()),
+        (2, FromHexErrorKind::UnexpectedCharacter('g'))
+    );
+    let err = decode_ascii_nibbles(b"123", &mut buf).unwrap_err();
+    assert_eq!(err.kind(), FromHexErrorKind::OutputTooSmall { required: 2 });
+}
+
+#[test]
+fn hex_float_format() {
+    use float::{hex_float, HexFloat};
+    for (value, expected) in [
+        (0.0, "0x0p+0"),
+        (-0.0, "-0x0p+0"),
+        (1.0, "0x1p+0"),
+        (12.0, "0x1.8p+3"),
+        (-0.5, "-0x1p-1"),
+        (0.1, "0x1.999999999999ap-4"),
+        (f64::MAX, "0x1.fffffffffffffp+1023"),
+        (f64::MIN_POSITIVE, "0x1p-1022"),
+        (f64::from_bits(1), "0x0.0000000000001p-1022"),
+        (
+            f64::from_bits(0x000f_ffff_ffff_ffff),
+            "0x0.fffffffffffffp-1022",
+        ),
+        (f64::INFINITY, "inf"),
+        (f64::NEG_INFINITY, "-inf"),
+        (f64::NAN, "nan"),
+    ] {
+        assert_eq!(hex_float(value).to_string(), expected);
+    }
+    assert_eq!(hex_float(f32::from_bits(1)).to_string(), "0x1p-149");
+    assert_eq!(hex_float(f32::MAX).to_string(), "0x1.fffffep+127");
+    assert_eq!(
+        HexFloat::new(-255.5).with_case(Case::Upper).to_string(),
+        "-0x1.FFP+7"
+    );
+    assert_eq!(
+        HexFloat::new(f64::NAN).with_case(Case::Upper).to_string(),
+        "NAN"
+    );
+}
+
+#[test]
+fn hex_float_round_trip() {
+    use float::{hex_float, parse_f32, parse_f64};
+    // a simple LCG, to cover all kinds of bit patterns
+    let mut state = 0x2545_f491_4f6c_dd1du64;
+    let mut next = || {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        state
+    };
+    for _ in 0..10_000 {
+        let bits = next();
+        for value in [f64::from_bits(bits), f64::from_bits(bits >> 12)] {
+            let parsed = parse_f64(&hex_float(value).to_string()).unwrap();
+            assert!(parsed.to_bits() == value.to_bits() || value.is_nan() && parsed.is_nan());
+        }
+        for value in [
+            f32::from_bits((bits >> 32) as u32),
+            f32::from_bits((bits >> 41) as u32),
+        ] {
+            let parsed = parse_f32(&hex_float(value).to_string()).unwrap();
+            assert!(parsed.to_bits() == value.to_bits() || value.is_nan() && parsed.is_nan());
+        }
+        // an f64 holds these exactly, so converting it rounds only once, like parse_f32 should
+        let exponent = (next() % 288) as i32 - 212;
+        let hex = format!("0x{:x}p{exponent}", next() >> 12);
+        let expected = parse_f64(&hex).unwrap() as f32;
+        assert_eq!(
+            parse_f32(&hex).unwrap().to_bits(),
+            expected.to_bits(),
+            "{hex}"
+        );
+    }
+}
+
+#[test]
+fn hex_float_parse_rounding() {
+    use float::{parse_f32, parse_f64};
+    let one_ulp = 1.0 + f32::EPSILON;
+    for (hex, expected) in [
+        ("0x1.000001p0", 1.0),
+        ("0x1.0000010000000000000000001p0", one_ulp),
+        ("0x1.0000011p0", one_ulp),
+        ("0x1.000003p0", 1.0 + 2.0 * f32::EPSILON),
+        ("0x0.8000008p1", 1.0),
+        ("0x100000080p-32", 1.0),
+        ("0x1.fffffefffp127", f32::MAX),
+        ("0x1p-149", f32::from_bits(1)),
+        ("0x1p-150", 0.0),
+        ("0x1.8p-150", f32::from_bits(1)),
+        ("0x1.fffffcp-127", f32::from_bits(0x7f_ffff)),
+        ("0x1.fffffep-127", f32::MIN_POSITIVE),
+        ("0x1p-99999999999999999999", 0.0),
+        ("-0x0.000p+5", -0.0),
+        ("1.8p1", 3.0),
+        (".8", 0.5),
+        ("0x1P+1", 2.0),
+        ("INF", f32::INFINITY),
+        ("-Infinity", f32::NEG_INFINITY),
+    ] {
+        let parsed = parse_f32(hex).unwrap();
+        assert_eq!(parsed.to_bits(), expected.to_bits(), "{hex:?}");
+    }
+    assert!(parse_f32("nan").unwrap().is_nan());
+    assert_eq!(
+        parse_f64("0x1.fffffffffffff8p1023").unwrap_err().kind(),
+        FromHexErrorKind::Overflow
+    );
+    assert_eq!(parse_f64("0x1.fffffffffffff7ffp1023").unwrap(), f64::MAX);
+    assert_eq!(
+        parse_f32("0x1p128").unwrap_err().kind(),
+        FromHexErrorKind::Overflow
+    );
+    assert_eq!(parse_f64("0x1p128").unwrap(), 2f64.powi(128));
+}
+
+#[test]
+fn hex_float_parse_errors() {
+    use float::{parse_f64, parse_f64_with_options};
+    for (hex, position, kind) in [
+        ("", 0, FromHexErrorKind::MissingDigits),
+        ("-", 1, FromHexErrorKind::MissingDigits),
+        ("0x", 2, FromHexErrorKind::MissingDigits),
+        ("0x.p1", 3, FromHexErrorKind::UnexpectedCharacter('p')),
+        ("0x1p+x", 5, FromHexErrorKind::UnexpectedCharacter('x')),
+        ("0x1p", 4, FromHexErrorKind::MissingDigits),
+        ("0x1p+", 5, FromHexErrorKind::MissingDigits),
+        ("0x1.8q3", 5, FromHexErrorKind::UnexpectedCharacter('q')),
+        ("0x1.8.", 5, FromHexErrorKind::UnexpectedCharacter('.')),
+        ("0x1p3€", 5, FromHexErrorKind::UnexpectedCharacter('€')),
+        ("0x1 ", 3, FromHexErrorKind::UnexpectedCharacter(' ')),
+        ("nan1", 0, FromHexErrorKind::UnexpectedCharacter('n')),
+        // like `decode`, only a lower case prefix is accepted
+        ("0X1p0", 1, FromHexErrorKind::UnexpectedCharacter('X')),
+        ("0x1.8p1024", 5, FromHexErrorKind::Overflow),
+        ("-0x1P+99999999999999999999", 4, FromHexErrorKind::Overflow),
+    ] {
+        let err = parse_f64(hex).unwrap_err();
+        assert_eq!((err.position(), err.kind()), (position, kind), "{hex:?}");
+    }
+    // 2^1024, without an exponent
+    let hex = format!("0x1{}", "0".repeat(256));
+    let err = parse_f64(&hex).unwrap_err();
+    assert_eq!(
+        (err.position(), err.kind()),
+        (hex.len() - 1, FromHexErrorKind::Overflow)
+    );
+    let required = DecodeOptions::new().with_prefix(PrefixPolicy::Required);
+    let err = parse_f64_with_options("-1p3", required).unwrap_err();
+    assert_eq!(
+        (err.position(), err.kind()),
+        (1, FromHexErrorKind::InvalidPrefix)
+    );
+    assert_eq!(parse_f64_with_options("-0x1p3", required).unwrap(), -8.0);
+    let forbidden = DecodeOptions::new().with_prefix(PrefixPolicy::Forbidden);
+    let err = parse_f64_with_options("0x1p3", forbidden).unwrap_err();
+    assert_eq!(
+        (err.position(), err.kind()),
+        (0, FromHexErrorKind::InvalidPrefix)
+    );
+    assert_eq!(parse_f64_with_options("1p3", forbidden).unwrap(), 8.0);
+}
+
+#[test]
+fn decode_words_endian() {
+    let hex = "00112233445566778899aabbccddeeff";
+    let mut words = [0u16; 8];
+    assert_eq!(decode_words(hex, Endian::Big, &mut words).unwrap(), 8);
+    assert_eq!(words[..2], [0x0011, 0x2233]);
+    let mut words = [0u32; 4];
+    assert_eq!(
+        decode_ascii_words(hex.as_bytes(), Endian::Little, &mut words).unwrap(),
+        4
+    );
+    assert_eq!(words, [0x33221100, 0x77665544, 0xbbaa9988, 0xffeeddcc]);
+    let mut words = [0u64; 3];
+    assert_eq!(decode_words(hex, Endian::Big, &mut words).unwrap(), 2);
+    assert_eq!(words, [0x0011223344556677, 0x8899aabbccddeeff, 0]);
+    let mut words = [0u128; 1];
+    assert_eq!(decode_words(hex, Endian::Little, &mut words).unwrap(), 1);
+    assert_eq!(words[0], 0xffeeddccbbaa99887766554433221100);
+    let mut words = [0u8; 2];
+    assert_eq!(
+        decode_words("0xc0de", Endian::Little, &mut words).unwrap(),
+        2
+    );
+    assert_eq!(words, [0xc0, 0xde]);
+
+    let options = DecodeOptions::new()
+        .with_separators(" ")
+        .with_odd_length(OddLengthPolicy::PadLeft);
+    let mut words = [0u16; 2];
+    assert_eq!(
+        decode_words_with_options("123 4567", Endian::Big, &mut words, options).unwrap(),
+        2
+    );
+    assert_eq!(words, [0x0123, 0x4567]);
+    assert_eq!(
+        decode_ascii_words_with_options(b"", Endian::Big, &mut words, options).unwrap(),
+        0
+    );
+}
+
+#[test]
+fn decode_words_errors() {
+    let mut words = [0u32; 2];
+    for (hex, position, kind) in [
+        ("deadbeef00", 8, FromHexErrorKind::IncompleteWord),
+        ("0x00", 2, FromHexErrorKind::IncompleteWord),
+        ("deadbeef0", 8, FromHexErrorKind::OddLength),
+        ("deadbeeg", 7, FromHexErrorKind::UnexpectedCharacter('g')),
+        (
+            "000000010000000200000003",
+            16,
+            FromHexErrorKind::OutputTooSmall { required: 3 },
+        ),
+        (
+            "0000000100000002000000030000",
+            16,
+            FromHexErrorKind::OutputTooSmall { required: 4 },
+        ),
+    ] {
+        let err = decode_words(hex, Endian::Big, &mut words).unwrap_err();
+        assert_eq!((err.position(), err.kind()), (position, kind), "{hex:?}");
+    }
+    let options = DecodeOptions::new().with_separators(" ");
+    let err = decode_ascii_words_with_options(b"1234 56 78 9a", Endian::Big, &mut words, options)
+        .unwrap_err();
+    assert_eq!(
+        (err.position(), err.kind()),
+        (11, FromHexErrorKind::IncompleteWord)
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn decode_words_vec_matches_decode_words() {
+    for hex in ["", "0x1234abcd", "1234abcd5678", "1234abcx"] {
+        let mut words = [0u32; 4];
+        let expected = decode_words(hex, Endian::Little, &mut words)
+            .map(|len| words[..len].to_vec())
+            .map_err(|e| (e.position(), e.kind()));
+        let actual =
+            decode_words_vec::<u32>(hex, Endian::Little).map_err(|e| (e.position(), e.kind()));
+        assert_eq!(actual, expected, "{hex:?}");
+        let expected = decode_ascii_words(hex.as_bytes(), Endian::Little, &mut words)
+            .map(|len| words[..len].to_vec())
+            .map_err(|e| (e.position(), e.kind()));
+        let actual = decode_ascii_words_vec::<u32>(hex.as_bytes(), Endian::Little)
+            .map_err(|e| (e.position(), e.kind()));
+        assert_eq!(actual, expected, "{hex:?}");
+    }
+    let options = DecodeOptions::new().with_separators(":");
+    assert_eq!(
+        decode_words_vec_with_options::<u16>("12:34", Endian::Big, options).unwrap(),
+        [0x1234]
+    );
+    assert_eq!(
+        decode_ascii_words_vec_with_options::<u16>(b"12:34", Endian::Little, options).unwrap(),
+        [0x3412]
+    );
+}
+
 #[cfg(all(feature = "proptest", feature = "std"))]
 mod property_tests {
     use super::*;
@@ -1,7 +1,10 @@
 #![forbid(unsafe_code)]
 
-use hexhex_impl::decode;
-use proc_macro::{Literal, TokenStream, TokenTree};
+use hexhex_impl::{
+    decode_ascii_with_options, decode_with_options, DecodeOptions, FromHexErrorKind,
+    OddLengthPolicy,
+};
+use proc_macro::{Literal, Span, TokenStream, TokenTree};
 
 macro_rules! return_compile_error {
     ($($fmt:tt)*) => {{
@@ -9,7 +12,7 @@ macro_rules! return_compile_error {
     }}
 }
 
-/// Convert a hex literal (string or byte string) to bytes at compile time.
+/// Convert a hex literal (string or byte string, possibly raw) to bytes at compile time.
 ///
 /// # Examples
 ///
@@ -24,43 +27,347 @@ macro_rules! return_compile_error {
 /// use hexhex_macros::hex_literal;
 /// let _ = hex_literal!("012"); // odd number of hex digits is not allowed
 /// ```
+///
+/// Unless `pad_left` is given, which adds an implicit leading `0` to inputs with an odd number of hex digits
+///
+/// ```
+/// use hexhex_macros::hex_literal;
+/// assert_eq!(hex_literal!("0xfff", pad_left), &[0x0f, 0xff]);
+/// assert_eq!(hex_literal!("ffff", pad_left), &[0xff, 0xff]);
+/// ```
+///
+/// Several literals are concatenated, and whitespace between bytes as well as `//` comments
+/// (up to the end of the line) within them are ignored
+///
+/// ```
+/// use hexhex_macros::hex_literal;
+/// const KEY: &[u8; 12] = hex_literal!(
+///     "00112233 44556677" // first half
+///     "8899aabb"
+/// );
+/// assert_eq!(KEY[4..], [0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xaa, 0xbb]);
+/// assert_eq!(hex_literal!("
+///     de ad // a comment
+///     be ef
+/// "), &[0xde, 0xad, 0xbe, 0xef]);
+/// ```
+///
+/// Each literal must consist of whole bytes, though
+///
+/// ```compile_fail
+/// use hexhex_macros::hex_literal;
+/// let _ = hex_literal!("012" "3");
+/// ```
+///
+/// Raw literals and escape sequences work as in any other string, but C strings are rejected
+///
+/// ```
+/// use hexhex_macros::hex_literal;
+/// assert_eq!(hex_literal!(r"c0 // \ comment"), &[0xc0]);
+/// assert_eq!(hex_literal!(br#"c0"#), &[0xc0]);
+/// assert_eq!(hex_literal!("\x63\x30 \
+///                          ff"), &[0xc0, 0xff]);
+/// ```
+///
+/// ```compile_fail
+/// use hexhex_macros::hex_literal;
+/// let _ = hex_literal!(c"c0");
+/// ```
 #[proc_macro]
 pub fn hex_literal(input: TokenStream) -> TokenStream {
+    let mut iter = input.into_iter().peekable();
+    let mut literals = Vec::new();
+    while let Some(TokenTree::Literal(lit)) = iter.peek() {
+        let mut content = match literal_content(lit) {
+            Ok(content) => content,
+            Err(msg) => return compile_error_at(&msg, lit.span()),
+        };
+        strip_comments(&mut content);
+        literals.push((content, lit.span()));
+        iter.next();
+    }
+    if literals.is_empty() {
+        match iter.next() {
+            None => return_compile_error!("expected a string or bytestring"),
+            Some(_) => return_compile_error!("unexpected argument"),
+        }
+    }
+
+    let options = match parse_options(&mut iter) {
+        Ok(options) => options,
+        Err(msg) => return compile_error(&msg),
+    };
+
+    // joined by a separator, so that each literal must consist of whole bytes
+    let content = literals
+        .iter()
+        .map(|(content, _)| content.as_slice())
+        .collect::<Vec<_>>()
+        .join(&b'\n');
+    let decoded = decode_content(&content, options);
+    let bytes = match decoded {
+        Ok(x) => x,
+        Err((mut position, kind)) => {
+            // report the position within the literal that contains it
+            for (content, span) in &literals {
+                if position <= content.len() {
+                    let msg = format!("error converting from hex: {kind} at position {position}");
+                    return compile_error_at(&msg, *span);
+                }
+                position -= content.len() + 1;
+            }
+            return_compile_error!("error converting from hex: {kind}");
+        }
+    };
+
+    [TokenTree::from(Literal::byte_string(&bytes))]
+        .into_iter()
+        .collect()
+}
+
+/// Read a file of hex text at compile time and convert it to bytes.
+///
+/// The path is relative to the root of the calling crate (the directory with its `Cargo.toml`).
+/// Like in [`hex_literal!`], whitespace between bytes and `//` comments are ignored (as is a byte order mark),
+/// and the `pad_left` option may be given. Decode errors show the line and column in the file.
+///
+/// The result is a `&'static [u8; N]` expression, and the crate is rebuilt when the file changes.
+///
+/// # Examples
+///
+/// ```
+/// use hexhex_macros::include_hex;
+/// let key: &[u8; 16] = include_hex!("testdata/key.hex");
+/// assert_eq!(key[..4], [0x00, 0x11, 0x22, 0x33]);
+/// ```
+///
+/// ```compile_fail
+/// use hexhex_macros::include_hex;
+/// let _ = include_hex!("testdata/missing.hex");
+/// ```
+#[proc_macro]
+pub fn include_hex(input: TokenStream) -> TokenStream {
     let mut iter = input.into_iter();
+    let (path, span) = match iter.next() {
+        Some(TokenTree::Literal(lit)) => match literal_content(&lit).map(String::from_utf8) {
+            Ok(Ok(path)) => (path, lit.span()),
+            Ok(Err(_)) => return compile_error_at("expected a UTF-8 path", lit.span()),
+            Err(msg) => return compile_error_at(&msg, lit.span()),
+        },
+        None => return_compile_error!("expected a file path"),
+        Some(tree) => return_compile_error!("unexpected argument {tree}"),
+    };
+    let options = match parse_options(&mut iter) {
+        Ok(options) => options,
+        Err(msg) => return compile_error(&msg),
+    };
+
+    let path = match std::env::var_os("CARGO_MANIFEST_DIR") {
+        Some(dir) => std::path::Path::new(&dir).join(&path),
+        None => path.into(),
+    };
+    let mut content = match std::fs::read(&path) {
+        Ok(content) => content,
+        Err(e) => return compile_error_at(&format!("couldn't read {}: {e}", path.display()), span),
+    };
+    // a byte order mark is not part of the text, so lines and columns are counted without it
+    if content.starts_with("\u{feff}".as_bytes()) {
+        content.drain(..3);
+    }
+    strip_comments(&mut content);
+    let bytes = match decode_content(&content, options) {
+        Ok(x) => x,
+        Err((position, kind)) => {
+            let before = String::from_utf8_lossy(&content[..position.min(content.len())]);
+            let line = before.matches('\n').count() + 1;
+            let column = before.rsplit('\n').next().map_or(0, |s| s.chars().count()) + 1;
+            let msg = format!(
+                "error converting from hex: {kind} at {}:{line}:{column}",
+                path.display()
+            );
+            return compile_error_at(&msg, span);
+        }
+    };
+
+    // `{ const _: &[u8] = ::core::include_bytes!(path); bytes }`, which makes the compiler track the file
+    use proc_macro::{Delimiter, Group, Ident, Punct, Spacing};
+    let ident = |name| TokenTree::Ident(Ident::new(name, Span::mixed_site()));
+    let punct = |c, spacing| TokenTree::Punct(Punct::new(c, spacing));
+    let include_bytes = [
+        punct(':', Spacing::Joint),
+        punct(':', Spacing::Joint),
+        ident("core"),
+        punct(':', Spacing::Joint),
+        punct(':', Spacing::Joint),
+        ident("include_bytes"),
+        punct('!', Spacing::Alone),
+        TokenTree::Group(Group::new(
+            Delimiter::Parenthesis,
+            [TokenTree::Literal(Literal::string(&path.to_string_lossy()))]
+                .into_iter()
+                .collect(),
+        )),
+    ];
+    let block = [
+        ident("const"),
+        ident("_"),
+        punct(':', Spacing::Alone),
+        punct('&', Spacing::Alone),
+        TokenTree::Group(Group::new(
+            Delimiter::Bracket,
+            [ident("u8")].into_iter().collect(),
+        )),
+        punct('=', Spacing::Alone),
+    ]
+    .into_iter()
+    .chain(include_bytes)
+    .chain([
+        punct(';', Spacing::Alone),
+        TokenTree::from(Literal::byte_string(&bytes)),
+    ]);
+    [TokenTree::Group(Group::new(
+        Delimiter::Brace,
+        block.collect(),
+    ))]
+    .into_iter()
+    .collect()
+}
+
+/// Parse the optional `, pad_left` after the input, or return an error message
+fn parse_options(iter: &mut impl Iterator<Item = TokenTree>) -> Result<DecodeOptions, String> {
+    let mut options = DecodeOptions::default().with_separators(" \t\r\n");
     match iter.next() {
-        Some(TokenTree::Literal(lit)) => {
-            let s = lit.to_string();
-            let content = if s.starts_with('"') && s.ends_with('"') {
-                // must be a string
-                &s[1..s.len() - 1]
-            } else if s.starts_with("b\"") && s.ends_with('"') {
-                &s[2..s.len() - 1]
-            } else {
-                return_compile_error!("unexpected argument {lit}");
-            };
-
-            let bytes = match decode(content) {
-                Ok(x) => x,
-                Err(e) => return_compile_error!("{e}"),
-            };
-
-            if let Some(tree) = iter.next() {
-                return_compile_error!("unexpected argument {tree}");
+        None => {}
+        Some(TokenTree::Punct(p)) if p.as_char() == ',' => match iter.next() {
+            Some(TokenTree::Ident(ident)) if ident.to_string() == "pad_left" => {
+                options.odd_length = OddLengthPolicy::PadLeft;
             }
-            [TokenTree::from(Literal::byte_string(&bytes))]
-                .into_iter()
-                .collect()
+            Some(tree) => return Err(format!("unexpected argument {tree}")),
+            None => return Err("expected an option after ','".to_string()),
+        },
+        Some(tree) => return Err(format!("unexpected argument {tree}")),
+    }
+    match iter.next() {
+        Some(tree) => Err(format!("unexpected argument {tree}")),
+        None => Ok(options),
+    }
+}
+
+/// Decode hex that may be surrounded by whitespace, or return the error position within `content` and its kind
+fn decode_content(
+    content: &[u8],
+    options: DecodeOptions,
+) -> Result<Vec<u8>, (usize, FromHexErrorKind)> {
+    // the prefix may only come after leading whitespace
+    let trimmed = content.trim_ascii_start();
+    let decoded = match std::str::from_utf8(trimmed) {
+        Ok(hex) => decode_with_options(hex, options),
+        // only byte strings can contain other bytes
+        Err(_) => decode_ascii_with_options(trimmed, options),
+    };
+    decoded.map_err(|e| (e.position() + content.len() - trimmed.len(), e.kind()))
+}
+
+/// The value of a string or byte string literal (including raw ones), or an error message
+fn literal_content(lit: &Literal) -> Result<Vec<u8>, String> {
+    let s = lit.to_string();
+    if s.starts_with("c\"") || s.starts_with("cr\"") || s.starts_with("cr#") {
+        return Err(format!(
+            "C string literals are not supported, use a string or byte string instead of {lit}"
+        ));
+    }
+    let unexpected = || format!("unexpected argument {lit}");
+    let rest = s.strip_prefix('b').unwrap_or(&s);
+    if let Some(rest) = rest.strip_prefix('r') {
+        // no escapes in raw literals, just a matching number of #s around the quotes
+        let hashes = &rest[..rest.len() - rest.trim_start_matches('#').len()];
+        let content = rest
+            .strip_prefix(&format!("{hashes}\""))
+            .and_then(|rest| rest.strip_suffix(&format!("\"{hashes}")))
+            .ok_or_else(unexpected)?;
+        return Ok(content.as_bytes().to_vec());
+    }
+    let content = rest
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+        .ok_or_else(unexpected)?;
+    unescape(content).ok_or_else(|| format!("invalid escape sequence in {lit}"))
+}
+
+/// The value of the content of a (non-raw) string or byte string literal
+///
+/// The compiler has already checked the escapes, so this is lenient about which ones
+/// are allowed in which kind of literal.
+fn unescape(content: &str) -> Option<Vec<u8>> {
+    let mut value = Vec::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            value.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+            continue;
+        }
+        match chars.next()? {
+            'n' => value.push(b'\n'),
+            'r' => value.push(b'\r'),
+            't' => value.push(b'\t'),
+            '\\' => value.push(b'\\'),
+            '0' => value.push(b'\0'),
+            '\'' => value.push(b'\''),
+            '"' => value.push(b'"'),
+            'x' => {
+                let digits = [chars.next()?, chars.next()?];
+                let digits: String = digits.iter().collect();
+                value.push(u8::from_str_radix(&digits, 16).ok()?);
+            }
+            'u' => {
+                if chars.next()? != '{' {
+                    return None;
+                }
+                let mut digits = String::new();
+                loop {
+                    match chars.next()? {
+                        '}' => break,
+                        '_' => {}
+                        c => digits.push(c),
+                    }
+                }
+                let c = char::from_u32(u32::from_str_radix(&digits, 16).ok()?)?;
+                value.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+            }
+            // line continuation, which skips the line break and leading whitespace of the next line
+            '\n' => while chars.next_if(|c| c.is_whitespace()).is_some() {},
+            _ => return None,
+        }
+    }
+    Some(value)
+}
+
+/// Replace `//` comments (up to the end of the line) with spaces, which keeps positions intact
+fn strip_comments(content: &mut [u8]) {
+    let mut in_comment = false;
+    for i in 0..content.len() {
+        if content[i] == b'\n' {
+            in_comment = false;
+        } else if content[i..].starts_with(b"//") {
+            in_comment = true;
+        }
+        if in_comment {
+            content[i] = b' ';
         }
-        None => return_compile_error!("expected a string or bytestring"),
-        _ => return_compile_error!("unexpected argument"),
     }
 }
 
 /// Return a token tree that causes a compile error with a nice error message
 fn compile_error(msg: &str) -> TokenStream {
+    compile_error_at(msg, Span::call_site())
+}
+
+/// Like [`compile_error`], but pointing at `span`
+fn compile_error_at(msg: &str, span: Span) -> TokenStream {
     // https://stackoverflow.com/a/75506477
-    use proc_macro::{Delimiter, Group, Ident, Punct, Spacing, Span};
-    [
+    use proc_macro::{Delimiter, Group, Ident, Punct, Spacing};
+    let span = span.resolved_at(Span::mixed_site());
+    let mut tokens = [
         TokenTree::Punct(Punct::new(':', Spacing::Joint)),
         TokenTree::Punct(Punct::new(':', Spacing::Joint)),
         TokenTree::Ident(Ident::new("std", Span::mixed_site())),
@@ -74,7 +381,9 @@ fn compile_error(msg: &str) -> TokenStream {
                 .into_iter()
                 .collect(),
         )),
-    ]
-    .into_iter()
-    .collect()
+    ];
+    for token in &mut tokens {
+        token.set_span(span);
+    }
+    tokens.into_iter().collect()
 }